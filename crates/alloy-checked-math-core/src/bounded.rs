@@ -0,0 +1,91 @@
+use crate::{Checked, CheckedMathError};
+
+/// Value of `T` restricted to `MIN..=MAX`.
+///
+/// Checked arithmetic fails with [`CheckedMathError::OutOfRange`] when the result leaves the bounds,
+/// and with the usual operator error when it leaves the range of `T`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bounded<T, const MIN: i128, const MAX: i128>(T);
+
+impl<T: Copy + TryInto<i128>, const MIN: i128, const MAX: i128> Bounded<T, MIN, MAX> {
+    const VALID_BOUNDS: () = assert!(MIN <= MAX, "MIN must not exceed MAX");
+
+    pub fn new(value: T) -> Result<Self, CheckedMathError> {
+        let _: () = Self::VALID_BOUNDS;
+
+        match value.try_into() {
+            Ok(v) if MIN <= v && v <= MAX => Ok(Bounded(value)),
+            _ => Err(CheckedMathError::OutOfRange),
+        }
+    }
+
+    pub fn get(self) -> T {
+        self.0
+    }
+}
+
+macro_rules! impl_bounded_binary_op {
+    ($($op:ident :: $method:ident),*) => { $(
+        impl<T: Copy + TryInto<i128>, const MIN: i128, const MAX: i128> std::ops::$op for Checked<Bounded<T, MIN, MAX>>
+        where
+            Checked<T>: std::ops::$op<Output = Checked<T>>,
+        {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                let value = std::ops::$op::$method(Checked::Ok(self?.0), Checked::Ok(other?.0))?;
+                Bounded::new(value).map(Checked::Ok).unwrap_or_else(Checked::Err)
+            }
+        }
+    )* }
+}
+
+impl_bounded_binary_op!(Add::add, Sub::sub, Mul::mul, Div::div, Rem::rem);
+
+impl<T: Copy + TryInto<i128>, const MIN: i128, const MAX: i128> std::ops::Neg for Checked<Bounded<T, MIN, MAX>>
+where
+    Checked<T>: std::ops::Neg<Output = Checked<T>>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let value = (-Checked::Ok(self?.0))?;
+        Bounded::new(value).map(Checked::Ok).unwrap_or_else(Checked::Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::aliases::*;
+
+    type Bps = Bounded<u16, 0, 10_000>;
+    type Tick = Bounded<i32, -887272, 887272>;
+
+    fn b<T: Copy + TryInto<i128>, const MIN: i128, const MAX: i128>(value: T) -> Checked<Bounded<T, MIN, MAX>> {
+        Checked::Ok(Bounded::new(value).unwrap())
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(Bps::new(10_000).is_ok());
+        assert_eq!(Bps::new(10_001), Err(CheckedMathError::OutOfRange));
+        assert_eq!(Tick::new(-887273), Err(CheckedMathError::OutOfRange));
+        assert_eq!(Bounded::<U256, 0, 100>::new(U256::MAX), Err(CheckedMathError::OutOfRange));
+    }
+
+    #[test]
+    fn test_checked_bounds() {
+        assert_eq!(b::<u16, 0, 10_000>(6_000) + b(4_000), b(10_000));
+        assert_eq!(b::<u16, 0, 10_000>(6_000) + b(4_001), Checked::Err(CheckedMathError::OutOfRange));
+        assert_eq!(b::<u16, 0, 10_000>(0) - b(1), Checked::Err(CheckedMathError::Sub));
+        assert_eq!(b::<u16, 0, 10_000>(30) * b(30), b(900));
+        assert_eq!(b::<u16, 0, 10_000>(300) * b(300), Checked::Err(CheckedMathError::Mul));
+        assert_eq!(b::<u16, 0, 10_000>(1) / b(0), Checked::Err(CheckedMathError::Div));
+
+        assert_eq!(-b::<i32, -887272, 887272>(887272), b(-887272));
+        assert_eq!(b::<i32, -887272, 887272>(887272) + b(1), Checked::Err(CheckedMathError::OutOfRange));
+        assert_eq!(-b::<i8, -128, 0>(-128), Checked::Err(CheckedMathError::Neg));
+        assert_eq!(-b::<i8, -100, 0>(-100), Checked::Err(CheckedMathError::OutOfRange));
+    }
+}
@@ -2,6 +2,15 @@
 
 use alloy_primitives::aliases::*;
 
+pub mod bounded;
+pub mod panicking;
+pub mod quantity;
+pub mod token_amount;
+
+pub use bounded::Bounded;
+pub use quantity::{Dimensionless, Quantity, Reduce, Unit, UnitDiv, UnitMul};
+pub use token_amount::{Rounding, TokenAmount};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CheckedMathError {
     Add,
@@ -10,6 +19,11 @@ pub enum CheckedMathError {
     Div,
     Rem,
     Neg,
+    Parse,
+    OutOfRange,
+    NaN,
+    Infinite,
+    Subnormal,
 }
 
 #[macro_export]
@@ -44,6 +58,15 @@ macro_rules! define_checked {
             fn unpack(value: Checked<Self>) -> Result<Self::Unpacked, $crate::CheckedMathError>;
         }
 
+        impl<T> From<Result<T, $crate::CheckedMathError>> for Checked<T> {
+            fn from(result: Result<T, $crate::CheckedMathError>) -> Self {
+                match result {
+                    Ok(v) => Checked::Ok(v),
+                    Err(e) => Checked::Err(e),
+                }
+            }
+        }
+
         impl<T> std::ops::FromResidual for Checked<T> {
             fn from_residual(residual: Result<std::convert::Infallible, $crate::CheckedMathError>) -> Self {
                 match residual {
@@ -134,6 +157,83 @@ impl_checked_math!(I0, I1, I8, I16, I24, I32, I40, I48, I56, I64, I72, I80, I88,
 impl_checked_math!(u8, u16, u32, u64, u128, usize);
 impl_checked_math!(i8, i16, i32, i64, i128);
 
+trait CheckedFloat: Sized {
+    fn checked_finite(self) -> Checked<Self>;
+}
+
+macro_rules! impl_checked_float {
+    ($($ty:ty),*) => { $(
+        impl CheckedFloat for $ty {
+            fn checked_finite(self) -> Checked<Self> {
+                if self.is_nan() {
+                    return Checked::Err(CheckedMathError::NaN);
+                }
+
+                if self.is_infinite() {
+                    return Checked::Err(CheckedMathError::Infinite);
+                }
+
+                #[cfg(feature = "deny_subnormal")]
+                if self.is_subnormal() {
+                    return Checked::Err(CheckedMathError::Subnormal);
+                }
+
+                Checked::Ok(self)
+            }
+        }
+
+        impl std::ops::Add for Checked<$ty> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                (self? + other?).checked_finite()
+            }
+        }
+
+        impl std::ops::Sub for Checked<$ty> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                (self? - other?).checked_finite()
+            }
+        }
+
+        impl std::ops::Mul for Checked<$ty> {
+            type Output = Self;
+
+            fn mul(self, other: Self) -> Self {
+                (self? * other?).checked_finite()
+            }
+        }
+
+        impl std::ops::Div for Checked<$ty> {
+            type Output = Self;
+
+            fn div(self, other: Self) -> Self {
+                (self? / other?).checked_finite()
+            }
+        }
+
+        impl std::ops::Rem for Checked<$ty> {
+            type Output = Self;
+
+            fn rem(self, other: Self) -> Self {
+                (self? % other?).checked_finite()
+            }
+        }
+
+        impl std::ops::Neg for Checked<$ty> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                (-self?).checked_finite()
+            }
+        }
+    )* }
+}
+
+impl_checked_float!(f32, f64);
+
 impl<T> CheckedPack for T {
     type Packed = T;
     fn pack(value: Self) -> Checked<Self::Packed> { Checked::Ok(value) }
@@ -229,4 +329,31 @@ mod tests {
             assert_eq!(l::<T>("1") % l::<T>("0"), Checked::Err(CheckedMathError::Rem));
         }
     }
+
+    #[test]
+    fn test_checked_float() {
+        {
+            type T = f64;
+            assert_eq!(l::<T>("1.5") + l::<T>("2.5"), l::<T>("4"));
+            assert_eq!(l::<T>("1") / l::<T>("4"), l::<T>("0.25"));
+            assert_eq!(l::<T>("1") / l::<T>("0"), Checked::Err(CheckedMathError::Infinite));
+            assert_eq!(l::<T>("0") / l::<T>("0"), Checked::Err(CheckedMathError::NaN));
+            assert_eq!(l::<T>("1") % l::<T>("0"), Checked::Err(CheckedMathError::NaN));
+            assert_eq!(Checked::Ok(T::MAX) * l::<T>("2"), Checked::Err(CheckedMathError::Infinite));
+            assert_eq!(Checked::Ok(T::NAN) - l::<T>("1"), Checked::Err(CheckedMathError::NaN));
+        }
+
+        {
+            type T = f32;
+            assert_eq!(-l::<T>("2"), l::<T>("-2"));
+            assert_eq!(-Checked::Ok(T::INFINITY), Checked::Err(CheckedMathError::Infinite));
+        }
+    }
+
+    #[cfg(feature = "deny_subnormal")]
+    #[test]
+    fn test_checked_float_subnormal() {
+        assert_eq!(Checked::Ok(f64::MIN_POSITIVE) / l::<f64>("2"), Checked::Err(CheckedMathError::Subnormal));
+        assert_eq!(l::<f64>("0") * l::<f64>("2"), l::<f64>("0"));
+    }
 }
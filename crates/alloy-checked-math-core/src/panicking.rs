@@ -0,0 +1,49 @@
+//! Helpers behind `checked_or_panic!` and `#[checked_fn(panic)]`.
+//!
+//! Every checked operator is expanded into a call to one of these functions, so with
+//! `#[track_caller]` the panic location is the offending operator rather than this module.
+
+use std::fmt::Debug;
+
+#[track_caller]
+pub fn binary<L: Clone + Debug, R: Clone + Debug, O, E: Debug>(operator: &str, left: L, right: R, f: impl FnOnce(L, R) -> Result<O, E>) -> O {
+    match f(left.clone(), right.clone()) {
+        Ok(value) => value,
+        Err(err) => panic!("checked math: `{left:?} {operator} {right:?}` failed with {err:?} at {}", std::panic::Location::caller()),
+    }
+}
+
+#[track_caller]
+pub fn unary<T: Clone + Debug, O, E: Debug>(operator: &str, operand: T, f: impl FnOnce(T) -> Result<O, E>) -> O {
+    match f(operand.clone()) {
+        Ok(value) => value,
+        Err(err) => panic!("checked math: `{operator}({operand:?})` failed with {err:?} at {}", std::panic::Location::caller()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Checked, CheckedMathError};
+
+    fn add(left: u8, right: u8) -> Result<u8, CheckedMathError> {
+        (Checked::Ok(left) + Checked::Ok(right)).result()
+    }
+
+    #[test]
+    fn test_binary() {
+        assert_eq!(binary("+", 1u8, 2u8, add), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "checked math: `255 + 1` failed with Add at crates/alloy-checked-math-core/src/panicking.rs:")]
+    fn test_binary_panic() {
+        binary("+", 255u8, 1u8, add);
+    }
+
+    #[test]
+    #[should_panic(expected = "checked math: `-(-128)` failed with Neg")]
+    fn test_unary_panic() {
+        unary("-", -128i8, |operand| (-Checked::Ok(operand)).result());
+    }
+}
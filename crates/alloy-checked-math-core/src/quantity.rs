@@ -0,0 +1,216 @@
+use std::marker::PhantomData;
+
+use crate::{Checked, CheckedMathError};
+
+/// Marker trait for compile-time units carried by [`Quantity`].
+pub trait Unit {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dimensionless;
+
+impl Unit for Dimensionless {}
+
+/// Unit of the product of an `A` quantity and a `B` quantity.
+pub struct UnitMul<A, B>(PhantomData<(A, B)>);
+
+/// Unit of the quotient of an `A` quantity by a `B` quantity.
+pub struct UnitDiv<A, B>(PhantomData<(A, B)>);
+
+impl<A: Unit, B: Unit> Unit for UnitMul<A, B> {}
+impl<A: Unit, B: Unit> Unit for UnitDiv<A, B> {}
+
+/// Cancels a composed unit, e.g. `A * (B / A)` into `B`.
+pub trait Reduce: Unit {
+    type Output: Unit;
+}
+
+impl<A: Unit, B: Unit> Reduce for UnitMul<A, UnitDiv<B, A>> {
+    type Output = B;
+}
+
+impl<A: Unit, B: Unit> Reduce for UnitMul<UnitDiv<A, B>, B> {
+    type Output = A;
+}
+
+impl<A: Unit, B: Unit> Reduce for UnitDiv<UnitMul<A, B>, B> {
+    type Output = A;
+}
+
+impl<A: Unit> Reduce for UnitDiv<A, A> {
+    type Output = Dimensionless;
+}
+
+pub struct Quantity<T, U> {
+    value: T,
+    unit: PhantomData<fn() -> U>,
+}
+
+impl<T, U: Unit> Quantity<T, U> {
+    pub const fn new(value: T) -> Self {
+        Quantity { value, unit: PhantomData }
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Rescales into unit `V` as `value * numerator / denominator`, rounding down.
+    pub fn convert<V: Unit>(self, numerator: T, denominator: T) -> Result<Quantity<T, V>, CheckedMathError>
+    where
+        Checked<T>: std::ops::Mul<Output = Checked<T>> + std::ops::Div<Output = Checked<T>>,
+    {
+        let value = Checked::Ok(self.value) * Checked::Ok(numerator) / Checked::Ok(denominator);
+        return value.result().map(Quantity::new);
+    }
+
+    pub fn reduce(self) -> Quantity<T, U::Output>
+    where
+        U: Reduce,
+    {
+        Quantity::new(self.value)
+    }
+}
+
+impl<T: std::fmt::Debug, U> std::fmt::Debug for Quantity<T, U> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Quantity").field(&self.value).finish()
+    }
+}
+
+impl<T: Clone, U> Clone for Quantity<T, U> {
+    fn clone(&self) -> Self {
+        Quantity { value: self.value.clone(), unit: PhantomData }
+    }
+}
+
+impl<T: Copy, U> Copy for Quantity<T, U> {}
+
+impl<T: PartialEq, U> PartialEq for Quantity<T, U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, U> Eq for Quantity<T, U> {}
+
+impl<T: PartialOrd, U> PartialOrd for Quantity<T, U> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord, U> Ord for Quantity<T, U> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+macro_rules! impl_same_unit_op {
+    ($($op:ident :: $method:ident),*) => { $(
+        impl<T, U: Unit> std::ops::$op for Checked<Quantity<T, U>>
+        where
+            Checked<T>: std::ops::$op<Output = Checked<T>>,
+        {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                let value = std::ops::$op::$method(Checked::Ok(self?.value), Checked::Ok(other?.value))?;
+                Checked::Ok(Quantity::new(value))
+            }
+        }
+    )* }
+}
+
+impl_same_unit_op!(Add::add, Sub::sub, Rem::rem);
+
+impl<T, A: Unit, B: Unit> std::ops::Mul<Checked<Quantity<T, B>>> for Checked<Quantity<T, A>>
+where
+    Checked<T>: std::ops::Mul<Output = Checked<T>>,
+{
+    type Output = Checked<Quantity<T, UnitMul<A, B>>>;
+
+    fn mul(self, other: Checked<Quantity<T, B>>) -> Self::Output {
+        let value = (Checked::Ok(self?.value) * Checked::Ok(other?.value))?;
+        Checked::Ok(Quantity::new(value))
+    }
+}
+
+impl<T, A: Unit, B: Unit> std::ops::Div<Checked<Quantity<T, B>>> for Checked<Quantity<T, A>>
+where
+    Checked<T>: std::ops::Div<Output = Checked<T>>,
+{
+    type Output = Checked<Quantity<T, UnitDiv<A, B>>>;
+
+    fn div(self, other: Checked<Quantity<T, B>>) -> Self::Output {
+        let value = (Checked::Ok(self?.value) / Checked::Ok(other?.value))?;
+        Checked::Ok(Quantity::new(value))
+    }
+}
+
+impl<T, U: Unit> std::ops::Neg for Checked<Quantity<T, U>>
+where
+    Checked<T>: std::ops::Neg<Output = Checked<T>>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let value = (-Checked::Ok(self?.value))?;
+        Checked::Ok(Quantity::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::aliases::*;
+
+    struct TokenA;
+    impl Unit for TokenA {}
+
+    struct TokenB;
+    impl Unit for TokenB {}
+
+    fn q<U: Unit>(value: u64) -> Checked<Quantity<U256, U>> {
+        Checked::Ok(Quantity::new(U256::from(value)))
+    }
+
+    #[test]
+    fn test_same_unit_ops() {
+        assert_eq!(q::<TokenA>(1) + q::<TokenA>(2), q::<TokenA>(3));
+        assert_eq!(q::<TokenA>(3) - q::<TokenA>(2), q::<TokenA>(1));
+        assert_eq!(q::<TokenA>(5) % q::<TokenA>(2), q::<TokenA>(1));
+        assert_eq!(q::<TokenA>(0) - q::<TokenA>(1), Checked::Err(CheckedMathError::Sub));
+        assert_eq!(Checked::Ok(Quantity::<U256, TokenA>::new(U256::MAX)) + q::<TokenA>(1), Checked::Err(CheckedMathError::Add));
+    }
+
+    #[test]
+    fn test_composed_units() {
+        let price: Checked<Quantity<U256, UnitDiv<TokenB, TokenA>>> = q::<TokenB>(6) / q::<TokenA>(3);
+        assert_eq!(price, Checked::Ok(Quantity::new(U256::from(2))));
+
+        let amount: Quantity<U256, TokenB> = (q::<TokenA>(5) * price).result().unwrap().reduce();
+        assert_eq!(amount, Quantity::new(U256::from(10)));
+
+        let ratio: Quantity<U256, Dimensionless> = (q::<TokenA>(6) / q::<TokenA>(3)).result().unwrap().reduce();
+        assert_eq!(ratio, Quantity::new(U256::from(2)));
+
+        assert_eq!((q::<TokenB>(1) / q::<TokenA>(0)).result(), Err(CheckedMathError::Div));
+    }
+
+    #[test]
+    fn test_convert() {
+        let bps = Quantity::<U256, TokenA>::new(U256::from(2_500));
+        let converted: Quantity<U256, TokenB> = bps.convert(U256::from(10).pow(U256::from(14)), U256::from(1)).unwrap();
+        assert_eq!(converted.into_value(), U256::from(25) * U256::from(10).pow(U256::from(16)));
+
+        let truncated: Quantity<U256, TokenB> = Quantity::<U256, TokenA>::new(U256::from(7)).convert(U256::from(1), U256::from(2)).unwrap();
+        assert_eq!(truncated.into_value(), U256::from(3));
+
+        let overflow = Quantity::<U256, TokenA>::new(U256::MAX).convert::<TokenB>(U256::from(2), U256::from(1));
+        assert_eq!(overflow, Err(CheckedMathError::Mul));
+    }
+}
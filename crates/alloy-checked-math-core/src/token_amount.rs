@@ -0,0 +1,155 @@
+use alloy_primitives::U256;
+use alloy_primitives::utils::{format_units, parse_units};
+
+use crate::{Checked, CheckedMathError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Raw `U256` token amount scaled by `10^DECIMALS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct TokenAmount<const DECIMALS: u8>(U256);
+
+fn pow10(exponent: u8) -> Result<U256, CheckedMathError> {
+    U256::from(10).checked_pow(U256::from(exponent)).ok_or(CheckedMathError::Mul)
+}
+
+impl<const DECIMALS: u8> TokenAmount<DECIMALS> {
+    const VALID_DECIMALS: () = assert!(DECIMALS <= 77, "10^DECIMALS must fit into U256");
+
+    pub const ZERO: Self = TokenAmount(U256::ZERO);
+
+    pub const fn new(raw: U256) -> Self {
+        let _: () = Self::VALID_DECIMALS;
+        TokenAmount(raw)
+    }
+
+    pub const fn raw(self) -> U256 {
+        self.0
+    }
+
+    /// Amount of raw units in one whole token.
+    pub fn one() -> Self {
+        Self::new(pow10(DECIMALS).unwrap())
+    }
+
+    pub fn rescale<const TO: u8>(self, rounding: Rounding) -> Result<TokenAmount<TO>, CheckedMathError> {
+        if TO >= DECIMALS {
+            let factor = pow10(TO - DECIMALS)?;
+            let raw = self.0.checked_mul(factor).ok_or(CheckedMathError::Mul)?;
+            return Ok(TokenAmount::new(raw));
+        }
+
+        let factor = pow10(DECIMALS - TO)?;
+        let (quotient, remainder) = self.0.div_rem(factor);
+
+        let raw = match rounding {
+            Rounding::Up if !remainder.is_zero() => quotient.checked_add(U256::ONE).ok_or(CheckedMathError::Add)?,
+            _ => quotient,
+        };
+
+        return Ok(TokenAmount::new(raw));
+    }
+
+    /// Parses a decimal string such as `"1.5"` with [`parse_units`].
+    ///
+    /// Unlike `parse_units`, negative amounts and amounts with more than `DECIMALS` fractional
+    /// digits are rejected instead of truncated, and scaling overflow is reported.
+    pub fn parse(amount: &str) -> Result<Self, CheckedMathError> {
+        let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+
+        if integer.starts_with('-') || fraction.len() > DECIMALS as usize {
+            return Err(CheckedMathError::Parse);
+        }
+
+        let integer = U256::from_str_radix(integer, 10).map_err(|_| CheckedMathError::Parse)?;
+        let raw: U256 = parse_units(amount, DECIMALS).map_err(|_| CheckedMathError::Parse)?.into();
+
+        // `parse_units` scales with wrapping multiplication.
+        if raw / pow10(DECIMALS)? != integer {
+            return Err(CheckedMathError::Mul);
+        }
+
+        return Ok(Self::new(raw));
+    }
+
+    pub fn format(self) -> String {
+        let _: () = Self::VALID_DECIMALS;
+        format_units(self.0, DECIMALS).unwrap()
+    }
+}
+
+impl<const DECIMALS: u8> std::fmt::Display for TokenAmount<DECIMALS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.format())
+    }
+}
+
+impl<const DECIMALS: u8> std::ops::Add for Checked<TokenAmount<DECIMALS>> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self?.0.checked_add(other?.0).map(|raw| Checked::Ok(TokenAmount(raw))).unwrap_or_else(|| Checked::Err(CheckedMathError::Add))
+    }
+}
+
+impl<const DECIMALS: u8> std::ops::Sub for Checked<TokenAmount<DECIMALS>> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self?.0.checked_sub(other?.0).map(|raw| Checked::Ok(TokenAmount(raw))).unwrap_or_else(|| Checked::Err(CheckedMathError::Sub))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Usdc = TokenAmount<6>;
+    type Wbtc = TokenAmount<8>;
+    type Weth = TokenAmount<18>;
+
+    #[test]
+    fn test_checked_add_sub() {
+        let a = Checked::Ok(Usdc::parse("1.5").unwrap());
+        let b = Checked::Ok(Usdc::parse("0.25").unwrap());
+
+        assert_eq!((a + b).result().unwrap().format(), "1.750000");
+        assert_eq!((a - b).result().unwrap().format(), "1.250000");
+        assert_eq!(b - a, Checked::Err(CheckedMathError::Sub));
+        assert_eq!(Checked::Ok(Usdc::new(U256::MAX)) + b, Checked::Err(CheckedMathError::Add));
+    }
+
+    #[test]
+    fn test_rescale() {
+        let usdc = Usdc::parse("12.345678").unwrap();
+        assert_eq!(usdc.rescale::<18>(Rounding::Down), Ok(Weth::parse("12.345678").unwrap()));
+
+        let weth = Weth::parse("0.123456789").unwrap();
+        assert_eq!(weth.rescale::<8>(Rounding::Down), Ok(Wbtc::parse("0.12345678").unwrap()));
+        assert_eq!(weth.rescale::<8>(Rounding::Up), Ok(Wbtc::parse("0.12345679").unwrap()));
+        assert_eq!(Weth::parse("1").unwrap().rescale::<6>(Rounding::Up), Ok(Usdc::parse("1").unwrap()));
+
+        assert_eq!(Usdc::new(U256::MAX).rescale::<18>(Rounding::Down), Err(CheckedMathError::Mul));
+        assert_eq!(Weth::new(U256::MAX).rescale::<0>(Rounding::Up), Ok(TokenAmount::new(U256::MAX / pow10(18).unwrap() + U256::ONE)));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(Usdc::parse("1").unwrap().raw(), U256::from(1_000_000));
+        assert_eq!(Usdc::parse("0.000001").unwrap().raw(), U256::ONE);
+        assert_eq!(Usdc::one().format(), "1.000000");
+        assert_eq!(Weth::parse(&Weth::new(U256::from(42)).format()), Ok(Weth::new(U256::from(42))));
+
+        assert_eq!(Usdc::parse("0.0000001"), Err(CheckedMathError::Parse));
+        assert_eq!(Usdc::parse("-1"), Err(CheckedMathError::Parse));
+        assert_eq!(Usdc::parse("abc"), Err(CheckedMathError::Parse));
+
+        let max = format!("{}", U256::MAX);
+        assert_eq!(TokenAmount::<0>::parse(&max), Ok(TokenAmount::new(U256::MAX)));
+        assert_eq!(Usdc::parse(&max), Err(CheckedMathError::Mul));
+    }
+}
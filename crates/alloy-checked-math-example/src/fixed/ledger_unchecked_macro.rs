@@ -0,0 +1,50 @@
+use alloy_checked_math::CheckedMathError;
+
+macro_rules! twice {
+    ($value:expr) => { [$value, $value] };
+}
+
+pub struct Ledger {
+    balance: u64,
+    fees: u64,
+}
+
+impl Ledger {
+    pub fn new(balance: u64) -> Self {
+        Ledger { balance, fees: 0 }
+    }
+
+    /// Credits an amount net of the fee.
+    pub fn credit(&mut self, amount: u64, bps: u64) -> Result<(), CheckedMathError> {
+        let fee = alloy_checked_math::unchecked! { amount * bps / 10_000 }; // rounds down
+        alloy_checked_math::unchecked! { self.fees += fee };
+        alloy_checked_math::unchecked! { self.balance += amount - fee };
+        Ok(())
+    }
+
+    pub fn scaled(&self, factors: &[u64]) -> Result<Vec<u64>, CheckedMathError> {
+        Ok(factors.iter().map(|factor| alloy_checked_math::unchecked! { self.balance * factor }).collect())
+    }
+
+    pub async fn settle(&mut self, payout: u64) -> Result<u64, CheckedMathError> {
+        alloy_checked_math::unchecked! { self.balance -= payout };
+        Ok(self.balance)
+    }
+
+    pub fn pair(&self, amount: u64) -> Result<[u64; 2], CheckedMathError> {
+        Ok(twice!(alloy_checked_math::unchecked! { self.balance + amount }))
+    }
+
+    pub fn average(&self, count: u64) -> u64 {
+        // Callers never pass zero.
+        alloy_checked_math::unchecked! { self.balance / count }
+    }
+}
+
+pub struct Fees;
+
+impl Fees { pub fn bump(&self, fee: u64) -> Result<u64, CheckedMathError> { Ok(alloy_checked_math::unchecked! { fee + 1 }) } }
+
+fn spread(bid: i64, ask: i64) -> Option<i64> {
+    Some(alloy_checked_math::unchecked! { ask - bid })
+}
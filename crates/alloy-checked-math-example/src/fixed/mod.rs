@@ -0,0 +1,21 @@
+// The output of `fix_file` on the lint's `tests/golden/fix/ledger.rs` for each policy, compiled here
+// to make sure fixes produce valid code. Regenerated by the lint's fix test with `GOLDEN=overwrite`.
+#![allow(dead_code)]
+
+pub mod ledger_checked_fn;
+pub mod ledger_checked_macro;
+pub mod ledger_unchecked_macro;
+
+#[cfg(test)]
+#[test]
+fn fixed_test() {
+    use alloy_checked_math::CheckedMathError;
+
+    // Arithmetic in the arguments of a custom macro is checked under both checked policies.
+    assert_eq!(ledger_checked_fn::Ledger::new(u64::MAX).pair(1), Err(CheckedMathError::Add));
+    assert_eq!(ledger_checked_macro::Ledger::new(u64::MAX).pair(1), Err(CheckedMathError::Add));
+    assert_eq!(ledger_checked_fn::Ledger::new(1).pair(1), Ok([2, 2]));
+
+    assert_eq!(ledger_checked_fn::Fees.bump(u64::MAX), Err(CheckedMathError::Add));
+    assert_eq!(ledger_checked_macro::Fees.bump(1), Ok(2));
+}
@@ -1,4 +1,5 @@
 #![feature(stmt_expr_attributes)]
 
+pub mod fixed;
 pub mod models;
 pub mod non_models;
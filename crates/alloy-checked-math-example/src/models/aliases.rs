@@ -0,0 +1,55 @@
+use alloy_checked_math::{checked_closure, checked_fn, try_checked, CheckedMathError};
+
+#[derive(Debug, PartialEq)]
+pub enum PoolError {
+    Math(CheckedMathError),
+    Empty,
+}
+
+impl From<CheckedMathError> for PoolError {
+    fn from(err: CheckedMathError) -> Self {
+        PoolError::Math(err)
+    }
+}
+
+// Expansions must not pick up a crate's own `Result` alias.
+pub type Result<T> = std::result::Result<T, PoolError>;
+
+#[checked_fn]
+pub fn share(amount: u64, shares: u64, total: u64) -> Result<u64> {
+    if total == 0 {
+        return Err(PoolError::Empty);
+    }
+
+    Ok(amount * shares / total)
+}
+
+#[checked_fn]
+pub const fn fee(amount: u64, bps: u64) -> std::result::Result<u64, CheckedMathError> {
+    Ok(amount * bps / 10_000)
+}
+
+pub fn rebate_or_zero(fee: u64, share: u64) -> u64 {
+    try_checked!(fee * share / 100).unwrap_or(0)
+}
+
+pub fn scaled(values: &[u64], rate: u64) -> Result<Vec<u64>> {
+    Ok(values.iter().map(checked_closure!(|&value| value * rate)).collect::<std::result::Result<_, _>>()?)
+}
+
+#[cfg(test)]
+#[test]
+fn aliases_test() {
+    assert_eq!(share(100, 1, 4), Ok(25));
+    assert_eq!(share(100, 1, 0), Err(PoolError::Empty));
+    assert_eq!(share(u64::MAX, 2, 4), Err(PoolError::Math(CheckedMathError::Mul)));
+
+    assert_eq!(fee(20_000, 30), Ok(60));
+    assert_eq!(fee(u64::MAX, 30), Err(CheckedMathError::Mul));
+
+    assert_eq!(rebate_or_zero(60, 50), 30);
+    assert_eq!(rebate_or_zero(u64::MAX, 50), 0);
+
+    assert_eq!(scaled(&[1, 2], 3), Ok(vec![3, 6]));
+    assert_eq!(scaled(&[u64::MAX], 3), Err(PoolError::Math(CheckedMathError::Mul)));
+}
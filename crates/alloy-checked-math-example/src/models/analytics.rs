@@ -0,0 +1,14 @@
+use alloy_checked_math::{checked_fn, CheckedMathError};
+
+#[checked_fn]
+pub fn average_price(volume: f64, notional: f64) -> Result<f64, CheckedMathError> {
+    Ok(notional / volume)
+}
+
+#[cfg(test)]
+#[test]
+fn analytics_test() {
+    assert_eq!(average_price(4.0, 10.0), Ok(2.5));
+    assert_eq!(average_price(0.0, 10.0), Err(CheckedMathError::Infinite));
+    assert_eq!(average_price(0.0, 0.0), Err(CheckedMathError::NaN));
+}
@@ -0,0 +1,16 @@
+use alloy_checked_math::{checked, Bounded, CheckedMathError};
+
+pub type Bps = Bounded<u16, 0, 10_000>;
+
+pub fn total_fee(protocol_fee: Bps, lp_fee: Bps) -> Result<Bps, CheckedMathError> {
+    Ok(checked! { protocol_fee + lp_fee })
+}
+
+#[cfg(test)]
+#[test]
+fn bounded_test() {
+    let bps = |v: u16| Bps::new(v).unwrap();
+
+    assert_eq!(total_fee(bps(30), bps(5)), Ok(bps(35)));
+    assert_eq!(total_fee(bps(9_000), bps(1_001)), Err(CheckedMathError::OutOfRange));
+}
@@ -0,0 +1,45 @@
+use alloy_checked_math::{checked_closure, checked_fn, CheckedMathError};
+
+#[checked_fn]
+pub fn scale_all(values: &[u64], rate: u64) -> Result<Vec<u64>, CheckedMathError> {
+    values.iter().map(checked_closure!(|&value| value * rate)).collect()
+}
+
+#[checked_fn]
+pub fn total_with_fees(values: &[u64], fee: u64) -> Result<u64, CheckedMathError> {
+    let with_fees = values.iter().map(|&value| -> Result<u64, CheckedMathError> { Ok(value + fee) });
+    with_fees.sum()
+}
+
+#[checked_fn]
+pub async fn settle(balance: u64, amount: u64) -> Result<u64, CheckedMathError> {
+    Ok(balance - amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+        match std::pin::pin!(future).poll(&mut context) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn iterator_adapters_test() {
+        assert_eq!(scale_all(&[1, 2, 3], 10), Ok(vec![10, 20, 30]));
+        assert_eq!(scale_all(&[1, u64::MAX], 2), Err(CheckedMathError::Mul));
+
+        assert_eq!(total_with_fees(&[1, 2, 3], 1), Ok(9));
+        assert_eq!(total_with_fees(&[1, u64::MAX], 1), Err(CheckedMathError::Add));
+    }
+
+    #[test]
+    fn async_fn_test() {
+        assert_eq!(block_on(settle(10, 4)), Ok(6));
+        assert_eq!(block_on(settle(4, 10)), Err(CheckedMathError::Sub));
+    }
+}
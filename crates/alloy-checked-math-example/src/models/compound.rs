@@ -0,0 +1,70 @@
+use alloy_checked_math::{checked_fn, checked_impl, CheckedMathError};
+
+pub struct Ledger {
+    pub balances: Vec<u64>,
+    pub total: u64,
+    pub lookups: usize,
+}
+
+#[checked_impl(panic)]
+impl Ledger {
+    fn next_index(&mut self) -> usize {
+        self.lookups += 1;
+        0
+    }
+
+    fn account(&mut self) -> &mut Ledger {
+        self.lookups += 1;
+        self
+    }
+}
+
+#[checked_fn]
+pub fn credit_indexed(ledger: &mut Ledger, amount: u64) -> Result<(), CheckedMathError> {
+    let idx = ledger.next_index();
+    ledger.balances[idx] += amount;
+    ledger.balances[ledger.lookups] += ledger.balances[idx];
+    Ok(())
+}
+
+#[checked_fn]
+pub fn debit_field(ledger: &mut Ledger, amount: u64) -> Result<(), CheckedMathError> {
+    ledger.account().total -= amount;
+    Ok(())
+}
+
+#[checked_fn]
+pub fn scale_deref(value: &mut u64, factor: u64) -> Result<(), CheckedMathError> {
+    *value *= factor;
+    Ok(())
+}
+
+#[checked_fn]
+pub fn double_self(value: u64) -> Result<u64, CheckedMathError> {
+    let mut value = value;
+    value += value;
+    Ok(value)
+}
+
+#[cfg(test)]
+#[test]
+fn compound_test() {
+    let mut ledger = Ledger { balances: vec![1, 2], total: 10, lookups: 0 };
+
+    assert_eq!(credit_indexed(&mut ledger, 5), Ok(()));
+    assert_eq!(ledger.balances, vec![6, 8]);
+    assert_eq!(ledger.lookups, 1);
+
+    assert_eq!(debit_field(&mut ledger, 4), Ok(()));
+    assert_eq!((ledger.total, ledger.lookups), (6, 2));
+    assert_eq!(debit_field(&mut ledger, 7), Err(CheckedMathError::Sub));
+    assert_eq!((ledger.total, ledger.lookups), (6, 3));
+
+    let mut value = 3;
+    assert_eq!(scale_deref(&mut value, 4), Ok(()));
+    assert_eq!(value, 12);
+    assert_eq!(scale_deref(&mut value, u64::MAX), Err(CheckedMathError::Mul));
+    assert_eq!(value, 12);
+
+    assert_eq!(double_self(21), Ok(42));
+}
@@ -0,0 +1,71 @@
+use alloy_primitives::aliases::*;
+
+use alloy_checked_math::{checked_fn, const_checked, try_checked, CheckedMathError};
+
+pub const BPS: u64 = 10_000;
+pub const MAX_FEE: u64 = const_checked!(BPS * 3 / 10);
+pub const DOUBLE_BPS: u64 = const_checked!(2 * BPS);
+pub const MAX_TICK: i32 = 887_272;
+pub const MIN_TICK: i32 = const_checked!(-MAX_TICK);
+pub const DEFAULT_FEE: Result<u64, CheckedMathError> = fee_of(1_000_000, 30);
+
+#[checked_fn]
+pub fn to_micros(amount: u64) -> Result<u64, CheckedMathError> {
+    Ok(amount * (1_000u64 * 1_000))
+}
+
+#[checked_fn]
+pub fn headroom(reserve: U256) -> Result<U256, CheckedMathError> {
+    Ok(U256::MAX - U256::ONE - reserve)
+}
+
+#[checked_fn]
+pub fn shift_down(value: i8) -> Result<i8, CheckedMathError> {
+    Ok(value + (i8::MIN + 1))
+}
+
+pub fn dust() -> Result<i128, CheckedMathError> {
+    try_checked! { -(i128::MAX / 7i128) % 1_000 }
+}
+
+#[checked_fn]
+pub const fn fee_of(amount: u64, bps: u64) -> Result<u64, CheckedMathError> {
+    Ok(amount * bps / BPS)
+}
+
+#[checked_fn]
+pub const fn rebate_cap(amount: u64) -> Result<u64, CheckedMathError> {
+    Ok(2 * (BPS - amount) - 1)
+}
+
+#[checked_fn]
+pub const fn align_tick(tick: i32, spacing: i32) -> Option<i32> {
+    let mut aligned = tick / spacing;
+    aligned *= spacing;
+    Some(aligned)
+}
+
+#[cfg(test)]
+#[test]
+fn constants_test() {
+    assert_eq!(to_micros(3), Ok(3_000_000));
+    assert_eq!(to_micros(u64::MAX), Err(CheckedMathError::Mul));
+
+    assert_eq!(headroom(U256::ZERO), Ok(U256::MAX - U256::ONE));
+    assert_eq!(headroom(U256::MAX), Err(CheckedMathError::Sub));
+
+    assert_eq!(shift_down(127), Ok(0));
+    assert_eq!(shift_down(-2), Err(CheckedMathError::Add));
+
+    assert_eq!(dust(), Ok(-(i128::MAX / 7) % 1_000));
+
+    assert_eq!((MAX_FEE, MIN_TICK), (3_000, -887_272));
+    assert_eq!(DEFAULT_FEE, Ok(3_000));
+    assert_eq!(DOUBLE_BPS, 20_000);
+    assert_eq!(rebate_cap(1_000), Ok(17_999));
+    assert_eq!(rebate_cap(BPS + 1), Err(CheckedMathError::Sub));
+    assert_eq!(fee_of(u64::MAX, 2), Err(CheckedMathError::Mul));
+    assert_eq!(align_tick(MIN_TICK, 60), Some(-887_220));
+    assert_eq!(align_tick(MIN_TICK, 0), None);
+    assert_eq!(align_tick(i32::MIN, -1), None);
+}
@@ -0,0 +1,36 @@
+use alloy_primitives::aliases::*;
+
+use alloy_checked_math::{try_checked, Checked, CheckedMathError};
+
+pub fn utilization(borrowed: U256, supplied: U256) -> U256 {
+    match try_checked! { borrowed * U256::from(10_000) / supplied } {
+        Ok(utilization) => utilization,
+        Err(CheckedMathError::Div) => U256::ZERO,
+        Err(_) => U256::from(10_000),
+    }
+}
+
+pub fn debt(principal: u64, interest: u64, penalty: u64) -> Checked<u64> {
+    let mut total = principal;
+
+    for fee in [interest, penalty] {
+        if let Err(err) = try_checked! { total += fee } {
+            return Checked::Err(err);
+        }
+    }
+
+    try_checked! { total - principal / 2 }.into()
+}
+
+#[cfg(test)]
+#[test]
+fn fallback_test() {
+    let u = U256::from;
+
+    assert_eq!(utilization(u(1), u(4)), u(2_500));
+    assert_eq!(utilization(u(1), u(0)), U256::ZERO);
+    assert_eq!(utilization(U256::MAX, u(1)), u(10_000));
+
+    assert_eq!(debt(10, 2, 1), Checked::Ok(8));
+    assert_eq!(debt(10, u64::MAX, 1), Checked::Err(CheckedMathError::Add));
+}
@@ -0,0 +1,54 @@
+use std::fmt::Write;
+
+use alloy_checked_math::{checked_fn, unchecked, CheckedMathError};
+
+macro_rules! sum {
+    ($($value:expr),* $(,)?) => {
+        [$($value),*].into_iter().sum::<u64>()
+    };
+}
+
+#[checked_fn]
+pub fn ladder(base: u64, step: u64, len: usize) -> Result<Vec<u64>, CheckedMathError> {
+    let mut rungs = vec![base + step; len + 1];
+    rungs.push(base * step);
+    Ok(rungs)
+}
+
+#[checked_fn]
+pub fn within_spread(bid: u64, ask: u64, max: u64) -> Result<bool, CheckedMathError> {
+    assert!(ask - bid <= max, "spread {} too wide", ask - bid);
+    Ok(matches!(ask - bid, 0 | 1 if max * 2 > 1))
+}
+
+#[checked_fn]
+pub fn describe(price: u64, amount: u64) -> Result<String, CheckedMathError> {
+    let mut out = format!("{} = {total}", price * amount, total = price * amount + 1);
+    write!(out, " / {}", unchecked!(amount.wrapping_sub(1))).unwrap();
+    Ok(out)
+}
+
+#[checked_fn(macros(sum))]
+pub fn weighted(a: u64, b: u64, weight: u64) -> Result<u64, CheckedMathError> {
+    Ok(sum!(a * weight, b * weight))
+}
+
+#[cfg(test)]
+#[test]
+fn macros_test() {
+    assert_eq!(ladder(1, 2, 1), Ok(vec![3, 3, 2]));
+    assert_eq!(ladder(u64::MAX, 1, 1), Err(CheckedMathError::Add));
+    assert_eq!(ladder(1, 1, usize::MAX), Err(CheckedMathError::Add));
+
+    assert_eq!(within_spread(3, 4, 5), Ok(true));
+    assert_eq!(within_spread(3, 8, 5), Ok(false));
+    assert_eq!(within_spread(5, 3, 5), Err(CheckedMathError::Sub));
+    assert_eq!(within_spread(3, 4, u64::MAX), Err(CheckedMathError::Mul));
+
+    assert_eq!(describe(2, 3), Ok("6 = 7 / 2".to_string()));
+    assert_eq!(describe(u64::MAX, 2), Err(CheckedMathError::Mul));
+    assert_eq!(describe(1, 0), Ok(format!("0 = 1 / {}", u64::MAX)));
+
+    assert_eq!(weighted(1, 2, 3), Ok(9));
+    assert_eq!(weighted(1, u64::MAX, 2), Err(CheckedMathError::Mul));
+}
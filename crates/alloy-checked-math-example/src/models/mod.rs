@@ -1,4 +1,19 @@
+pub mod aliases;
+pub mod analytics;
+pub mod bounded;
+pub mod closures;
+pub mod compound;
+pub mod constants;
 pub mod example;
+pub mod fallback;
+pub mod macros;
+pub mod namespaces;
+pub mod propagation;
+pub mod quantity;
+pub mod scopes;
+pub mod simulation;
+pub mod statements;
+pub mod token_amount;
 
 #[cfg(test)]
 #[test]
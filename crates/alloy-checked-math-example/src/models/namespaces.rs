@@ -0,0 +1,37 @@
+use alloy_checked_math::{checked, checked_closure, checked_fn, try_checked, CheckedMathError};
+
+pub mod prelude {
+    pub use alloy_checked_math as math;
+}
+
+#[checked_fn(crate = crate::models::namespaces::prelude::math)]
+pub fn fee(amount: u64, bps: u64) -> Result<u64, CheckedMathError> {
+    Ok(amount * bps / 10_000)
+}
+
+pub fn rebate(fee: u64, share: u64) -> Result<u64, CheckedMathError> {
+    Ok(checked!(crate = prelude::math; fee * share / 100))
+}
+
+pub fn rebate_or_zero(fee: u64, share: u64) -> u64 {
+    try_checked!(crate = self::prelude::math; fee * share / 100).unwrap_or(0)
+}
+
+pub fn doubled(values: &[u64]) -> Result<Vec<u64>, CheckedMathError> {
+    values.iter().map(checked_closure!(crate = prelude::math; |&value| value * 2)).collect()
+}
+
+#[cfg(test)]
+#[test]
+fn namespaces_test() {
+    assert_eq!(fee(20_000, 30), Ok(60));
+    assert_eq!(fee(u64::MAX, 30), Err(CheckedMathError::Mul));
+
+    assert_eq!(rebate(60, 50), Ok(30));
+    assert_eq!(rebate(u64::MAX, 50), Err(CheckedMathError::Mul));
+    assert_eq!(rebate_or_zero(60, 50), 30);
+    assert_eq!(rebate_or_zero(u64::MAX, 50), 0);
+
+    assert_eq!(doubled(&[1, 2]), Ok(vec![2, 4]));
+    assert_eq!(doubled(&[u64::MAX]), Err(CheckedMathError::Mul));
+}
@@ -0,0 +1,53 @@
+use alloy_primitives::aliases::*;
+
+use alloy_checked_math::{checked_fn, Checked, CheckedMathError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoolError {
+    Math(CheckedMathError),
+    Empty,
+}
+
+#[checked_fn]
+pub fn share_of(amount: U256, total: U256) -> Option<U256> {
+    Some(amount * U256::from(10_000) / total)
+}
+
+#[checked_fn]
+pub fn spread(bid: U256, ask: U256) -> Checked<U256> {
+    Checked::Ok(ask - bid)
+}
+
+#[checked_fn(map_err = PoolError::Math)]
+pub fn withdraw(reserve: U256, amount: U256) -> Result<U256, PoolError> {
+    if reserve.is_zero() {
+        return Err(PoolError::Empty);
+    }
+
+    Ok(reserve - amount)
+}
+
+#[checked_fn]
+pub fn shares(amounts: &[U256], total: U256) -> Option<Vec<U256>> {
+    amounts.iter().map(|&amount| -> Option<U256> { Some(amount * U256::from(10_000) / total) }).collect()
+}
+
+#[cfg(test)]
+#[test]
+fn propagation_test() {
+    let u = U256::from;
+
+    assert_eq!(share_of(u(1), u(4)), Some(u(2_500)));
+    assert_eq!(share_of(u(1), u(0)), None);
+    assert_eq!(share_of(U256::MAX, u(1)), None);
+
+    assert_eq!(spread(u(3), u(5)), Checked::Ok(u(2)));
+    assert_eq!(spread(u(5), u(3)), Checked::Err(CheckedMathError::Sub));
+
+    assert_eq!(withdraw(u(5), u(3)), Ok(u(2)));
+    assert_eq!(withdraw(u(3), u(5)), Err(PoolError::Math(CheckedMathError::Sub)));
+    assert_eq!(withdraw(u(0), u(5)), Err(PoolError::Empty));
+
+    assert_eq!(shares(&[u(1), u(3)], u(4)), Some(vec![u(2_500), u(7_500)]));
+    assert_eq!(shares(&[u(1)], u(0)), None);
+}
@@ -0,0 +1,34 @@
+use alloy_primitives::aliases::*;
+
+use alloy_checked_math::{checked, checked_fn, CheckedMathError, Quantity, Unit, UnitDiv};
+
+pub struct Usdc;
+impl Unit for Usdc {}
+
+pub struct Weth;
+impl Unit for Weth {}
+
+pub type Price = Quantity<U256, UnitDiv<Usdc, Weth>>;
+
+#[checked_fn]
+pub fn quote(amount_in: Quantity<U256, Weth>, fee: Quantity<U256, Weth>, price: Price) -> Result<Quantity<U256, Usdc>, CheckedMathError> {
+    let net = amount_in - fee;
+    Ok((net * price).reduce())
+}
+
+pub fn total(a: Quantity<U256, Usdc>, b: Quantity<U256, Usdc>) -> Result<Quantity<U256, Usdc>, CheckedMathError> {
+    Ok(checked! { a + b })
+}
+
+#[cfg(test)]
+#[test]
+fn quantity_test() {
+    let weth = |v: u64| Quantity::<U256, Weth>::new(U256::from(v));
+    let usdc = |v: u64| Quantity::<U256, Usdc>::new(U256::from(v));
+
+    assert_eq!(quote(weth(10), weth(1), Price::new(U256::from(3))), Ok(usdc(27)));
+    assert_eq!(quote(weth(1), weth(2), Price::new(U256::from(3))), Err(CheckedMathError::Sub));
+
+    assert_eq!(total(usdc(1), usdc(2)), Ok(usdc(3)));
+    assert_eq!(total(Quantity::new(U256::MAX), usdc(1)), Err(CheckedMathError::Add));
+}
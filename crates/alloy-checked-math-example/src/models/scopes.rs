@@ -0,0 +1,89 @@
+use alloy_checked_math::{checked_impl, checked_mod, unchecked_fn, CheckedMathError};
+
+pub struct Vault {
+    pub assets: u64,
+    pub shares: u64,
+}
+
+#[checked_impl]
+impl Vault {
+    pub fn deposit(&mut self, assets: u64) -> Result<u64, CheckedMathError> {
+        let shares = assets * self.shares / self.assets;
+        self.assets += assets;
+        self.shares += shares;
+        Ok(shares)
+    }
+
+    pub fn price(&self) -> Option<u64> {
+        Some(self.assets / self.shares)
+    }
+
+    #[unchecked_fn]
+    pub fn wrapping_total(&self) -> u64 {
+        self.assets.wrapping_add(self.shares)
+    }
+}
+
+#[checked_impl]
+pub trait Fee {
+    fn bps(&self) -> u64;
+
+    fn fee(&self, amount: u64) -> Result<u64, CheckedMathError> {
+        Ok(amount * self.bps() / 10_000)
+    }
+}
+
+impl Fee for Vault {
+    fn bps(&self) -> u64 {
+        30
+    }
+}
+
+#[checked_mod]
+pub mod rates {
+    use super::*;
+
+    // The constant is evaluated at compile time, but the closure body runs at runtime and is checked.
+    pub const GROWTH: fn(u64) -> Option<u64> = |value: u64| -> Option<u64> { Some(value * 11 / 10) };
+
+    pub fn compound(rate: u64, periods: u32) -> Result<u64, CheckedMathError> {
+        let mut value = 1_000_000;
+        for _ in 0..periods {
+            value = value * (1_000_000 + rate) / 1_000_000;
+        }
+        Ok(value)
+    }
+
+    #[checked_impl(panic)]
+    impl super::Vault {
+        pub const DOUBLE: fn(u64) -> u64 = |shares| shares * 2;
+
+        pub fn assets_per_share(&self) -> u64 {
+            self.assets / self.shares
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn scopes_test() {
+    let mut vault = Vault { assets: 100, shares: 10 };
+
+    assert_eq!(vault.deposit(50), Ok(5));
+    assert_eq!(vault.price(), Some(10));
+    assert_eq!(vault.deposit(u64::MAX), Err(CheckedMathError::Mul));
+    assert_eq!(vault.fee(1_000_000), Ok(3_000));
+    assert_eq!(vault.fee(u64::MAX), Err(CheckedMathError::Mul));
+    assert_eq!(vault.assets_per_share(), 10);
+
+    assert_eq!(rates::compound(100_000, 2), Ok(1_210_000));
+    assert_eq!(rates::compound(u64::MAX, 1), Err(CheckedMathError::Add));
+    assert_eq!(rates::GROWTH(100), Some(110));
+    assert_eq!(rates::GROWTH(u64::MAX), None);
+    assert_eq!(Vault::DOUBLE(21), 42);
+    assert!(std::panic::catch_unwind(|| Vault::DOUBLE(u64::MAX)).is_err());
+
+    let empty = Vault { assets: 0, shares: 0 };
+    assert_eq!(empty.price(), None);
+    assert_eq!(empty.wrapping_total(), 0);
+}
@@ -0,0 +1,41 @@
+use alloy_checked_math::{checked_fn, checked_or_panic};
+
+#[checked_fn(panic)]
+pub fn accrue(balance: u8, interest: u8) -> u8 {
+    let mut total = balance;
+    total += interest;
+    total * 2
+}
+
+pub fn drain(balance: i8) -> i8 {
+    checked_or_panic! { -(balance - 1) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulation_test() {
+        assert_eq!(accrue(10, 5), 30);
+        assert_eq!(drain(5), -4);
+    }
+
+    #[test]
+    #[should_panic(expected = "checked math: `200 + 100` failed with Add at crates/alloy-checked-math-example/src/models/simulation.rs:6:11")]
+    fn accrue_overflow_test() {
+        accrue(200, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "checked math: `128 * 2` failed with Mul at crates/alloy-checked-math-example/src/models/simulation.rs:7:11")]
+    fn accrue_double_overflow_test() {
+        accrue(100, 28);
+    }
+
+    #[test]
+    #[should_panic(expected = "checked math: `-128 - 1` failed with Sub")]
+    fn drain_overflow_test() {
+        drain(i8::MIN);
+    }
+}
@@ -0,0 +1,78 @@
+use alloy_primitives::aliases::*;
+
+use alloy_checked_math::{checked, checked_fn, try_checked, CheckedMathError};
+
+pub fn amount_out(x: U256, y: U256, z: U256, fee: U256) -> Result<U256, CheckedMathError> {
+    let out = checked! {
+        let a = x * y;
+        let b = a / z;
+        b - fee
+    };
+
+    Ok(out)
+}
+
+pub fn average(values: &[u64]) -> Result<u64, CheckedMathError> {
+    checked! {
+        const MIN_SAMPLES: usize = 1 + 1;
+
+        fn sum(values: &[u64]) -> Option<u64> {
+            let mut total = 0u64;
+            for &value in values {
+                total += value;
+            }
+            Some(total)
+        }
+
+        if values.len() < MIN_SAMPLES {
+            return Ok(0);
+        }
+
+        let total = sum(values).ok_or(CheckedMathError::Add)?;
+        Ok(total / values.len() as u64)
+    }
+}
+
+#[checked_fn]
+pub fn nested(values: &[u64]) -> Result<u64, CheckedMathError> {
+    fn double(value: u64) -> Option<u64> {
+        Some(value * 2)
+    }
+
+    let mut total = 0;
+    for &value in values {
+        total += double(value).ok_or(CheckedMathError::Mul)?;
+    }
+    Ok(total)
+}
+
+pub fn accumulate(total: &mut u64, amounts: &[u64]) -> Result<(), CheckedMathError> {
+    try_checked! {
+        for &amount in amounts {
+            *total += amount;
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn statements_test() {
+    let u = U256::from;
+
+    assert_eq!(amount_out(u(6), u(4), u(3), u(1)), Ok(u(7)));
+    assert_eq!(amount_out(u(6), u(4), u(0), u(1)), Err(CheckedMathError::Div));
+    assert_eq!(amount_out(u(6), u(4), u(3), u(9)), Err(CheckedMathError::Sub));
+
+    assert_eq!(average(&[2, 4, 6]), Ok(4));
+    assert_eq!(average(&[2]), Ok(0));
+    assert_eq!(average(&[u64::MAX, 1]), Err(CheckedMathError::Add));
+
+    assert_eq!(nested(&[1, 2]), Ok(6));
+    assert_eq!(nested(&[u64::MAX]), Err(CheckedMathError::Mul));
+    assert_eq!(nested(&[u64::MAX / 2, u64::MAX / 2]), Err(CheckedMathError::Add));
+
+    let mut total = 1;
+    assert_eq!(accumulate(&mut total, &[2, 3]), Ok(()));
+    assert_eq!(total, 6);
+    assert_eq!(accumulate(&mut total, &[u64::MAX]), Err(CheckedMathError::Add));
+}
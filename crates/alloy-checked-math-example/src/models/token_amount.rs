@@ -0,0 +1,21 @@
+use alloy_checked_math::{checked_fn, CheckedMathError, Rounding, TokenAmount};
+
+pub type Usdc = TokenAmount<6>;
+pub type Weth = TokenAmount<18>;
+
+#[checked_fn]
+pub fn top_up(balance: Weth, deposit: Usdc, fee: Usdc) -> Result<Weth, CheckedMathError> {
+    let net = deposit - fee;
+    Ok(balance + net.rescale::<18>(Rounding::Down)?)
+}
+
+#[cfg(test)]
+#[test]
+fn token_amount_test() {
+    let balance = Weth::parse("1.5").unwrap();
+    let deposit = Usdc::parse("2.25").unwrap();
+    let fee = Usdc::parse("0.25").unwrap();
+
+    assert_eq!(top_up(balance, deposit, fee).map(Weth::format), Ok("3.500000000000000000".to_string()));
+    assert_eq!(top_up(balance, fee, deposit), Err(CheckedMathError::Sub));
+}
@@ -0,0 +1,351 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::report::{ParseError, SourceSpan, Suppression, SuppressionKind};
+
+/// A way of bypassing checked math that a security review should look at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EscapeHatchKind {
+    UncheckedMacro,
+    UncheckedFn,
+    AllowAttribute,
+    AllowComment,
+    /// A `wrapping_*` method, which silently wraps around on overflow.
+    WrappingMethod,
+    /// A `saturating_*` method, which silently clamps on overflow.
+    SaturatingMethod,
+}
+
+impl EscapeHatchKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EscapeHatchKind::UncheckedMacro => "unchecked_macro",
+            EscapeHatchKind::UncheckedFn => "unchecked_fn",
+            EscapeHatchKind::AllowAttribute => "allow_attribute",
+            EscapeHatchKind::AllowComment => "allow_comment",
+            EscapeHatchKind::WrappingMethod => "wrapping_method",
+            EscapeHatchKind::SaturatingMethod => "saturating_method",
+        }
+    }
+
+    /// How the escape hatch looks in the source.
+    pub fn label(&self) -> &'static str {
+        match self {
+            EscapeHatchKind::UncheckedMacro => "unchecked!",
+            EscapeHatchKind::UncheckedFn => "#[unchecked_fn]",
+            EscapeHatchKind::AllowAttribute => "#[allow_unchecked_math]",
+            EscapeHatchKind::AllowComment => "// checked-math: allow",
+            EscapeHatchKind::WrappingMethod => "wrapping_*",
+            EscapeHatchKind::SaturatingMethod => "saturating_*",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EscapeHatch {
+    pub file: PathBuf,
+    pub span: SourceSpan,
+    /// The qualified path of the enclosing function, or of the function an attribute is on.
+    pub function: Option<String>,
+    pub kind: EscapeHatchKind,
+    /// The macro invocation, function name, attribute, comment or method call.
+    pub code: String,
+    /// The arithmetic expressions it lets through unchecked.
+    pub arithmetic: Vec<String>,
+    /// The reason of a suppression, or the comment directly above the escape hatch.
+    pub justification: Option<String>,
+}
+
+impl From<&Suppression> for EscapeHatch {
+    fn from(suppression: &Suppression) -> Self {
+        let SourceSpan { start, end } = suppression.span;
+        let line = suppression.source_line.chars().skip(start.column - 1);
+
+        let code: String = match end.line == start.line {
+            true => line.take(end.column - start.column).collect(),
+            false => line.collect(),
+        };
+
+        EscapeHatch {
+            file: suppression.file.clone(),
+            span: suppression.span,
+            function: suppression.function.clone(),
+            kind: match suppression.kind {
+                SuppressionKind::Attribute => EscapeHatchKind::AllowAttribute,
+                SuppressionKind::Comment => EscapeHatchKind::AllowComment,
+            },
+            code: code.trim().to_string(),
+            arithmetic: suppression.violations.iter().map(|violation| violation.expression.clone()).collect(),
+            justification: suppression.is_justified().then(|| suppression.reason.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuditFormat {
+    /// Tables for a review document or pull request comment.
+    #[default]
+    Markdown,
+    /// One JSON object per escape hatch or parse error.
+    Json,
+}
+
+impl std::str::FromStr for AuditFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            "markdown" => Ok(AuditFormat::Markdown),
+            "json" => Ok(AuditFormat::Json),
+            _ => Err(format!("unknown audit format `{name}`, expected `markdown` or `json`")),
+        };
+    }
+}
+
+/// Every escape hatch in the audited files, ordered by file and location.
+#[derive(Debug, Clone, Default)]
+pub struct Audit {
+    /// The audited directory, or the parent directory when a single file was audited.
+    pub root: PathBuf,
+    pub escape_hatches: Vec<EscapeHatch>,
+    pub parse_errors: Vec<ParseError>,
+}
+
+/// The escape hatches added and removed between two audits.
+#[derive(Debug, Clone, Default)]
+pub struct AuditDiff {
+    pub root: PathBuf,
+    pub added: Vec<EscapeHatch>,
+    pub removed: Vec<EscapeHatch>,
+}
+
+impl Audit {
+    /// Audits sources that are not on disk, such as the files of a git revision. Like the files
+    /// of a checked directory, they are filtered by the configured globs.
+    pub fn from_sources<I: IntoIterator<Item = (PathBuf, String)>>(root: &Path, sources: I, config: &Config) -> Audit {
+        let mut audit = Audit { root: root.to_path_buf(), ..Audit::default() };
+
+        for (file, content) in sources.into_iter().filter(|(file, _)| config.is_included(&config.relative_path(root, file))) {
+            audit.add(crate::check_file(root, &file, &content, config));
+        }
+
+        audit.sort();
+        return audit;
+    }
+
+    fn add(&mut self, checked: Result<crate::FileCheck, ParseError>) {
+        match checked {
+            Ok(checked) => {
+                self.escape_hatches.extend(checked.escape_hatches);
+                self.escape_hatches.extend(checked.suppressions.iter().map(EscapeHatch::from));
+            },
+            Err(error) => self.parse_errors.push(error),
+        }
+    }
+
+    fn sort(&mut self) {
+        self.escape_hatches.sort_by(|a, b| (&a.file, a.span.start.line, a.span.start.column).cmp(&(&b.file, b.span.start.line, b.span.start.column)));
+    }
+
+    /// Appends the results of another audit, keeping this audit's root.
+    pub fn merge(&mut self, other: Audit) {
+        self.escape_hatches.extend(other.escape_hatches);
+        self.parse_errors.extend(other.parse_errors);
+    }
+
+    pub fn relative_path<'a>(&self, file: &'a Path) -> &'a Path {
+        file.strip_prefix(&self.root).unwrap_or(file)
+    }
+
+    // Escape hatches match across revisions by file, function, kind and code without whitespace, so
+    // unrelated edits that move them around don't show up as changes.
+    fn key(&self, hatch: &EscapeHatch) -> (PathBuf, Option<String>, EscapeHatchKind, String) {
+        let code = hatch.code.chars().filter(|c| !c.is_whitespace()).collect();
+        (self.relative_path(&hatch.file).to_path_buf(), hatch.function.clone(), hatch.kind, code)
+    }
+
+    /// The escape hatches of this audit that are not in `base`, and those of `base` that are gone.
+    /// Identical escape hatches in the same function are matched by count.
+    pub fn diff(&self, base: &Audit) -> AuditDiff {
+        let mut unmatched: Vec<_> = base.escape_hatches.iter().map(|hatch| Some((base.key(hatch), hatch))).collect();
+        let mut added = Vec::new();
+
+        for hatch in &self.escape_hatches {
+            let key = self.key(hatch);
+
+            match unmatched.iter_mut().find(|entry| entry.as_ref().is_some_and(|(other, _)| *other == key)) {
+                Some(entry) => *entry = None,
+                None => added.push(hatch.clone()),
+            }
+        }
+
+        let removed = unmatched.into_iter().flatten().map(|(_, hatch)| hatch.clone()).collect();
+        return AuditDiff { root: self.root.clone(), added, removed };
+    }
+
+    pub fn format(&self, format: AuditFormat) -> String {
+        match format {
+            AuditFormat::Markdown => self.markdown(),
+            AuditFormat::Json => self.json(),
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let mut markdown = String::from("# Escape hatches\n\n");
+
+        match self.escape_hatches.len() {
+            0 => markdown.push_str("No escape hatches found.\n"),
+            count => {
+                writeln!(markdown, "Found {count} escape hatches, {} without a justification.\n", self.unjustified()).unwrap();
+                markdown_table(&mut markdown, &self.root, &self.escape_hatches);
+            },
+        }
+
+        markdown_parse_errors(&mut markdown, &self.root, &self.parse_errors);
+        return markdown;
+    }
+
+    fn json(&self) -> String {
+        let hatches = self.escape_hatches.iter().map(|hatch| json_hatch(&self.root, hatch, None));
+        let parse_errors = self.parse_errors.iter().map(|error| json_parse_error(&self.root, error));
+        return hatches.chain(parse_errors).map(|line| format!("{line}\n")).collect();
+    }
+
+    fn unjustified(&self) -> usize {
+        self.escape_hatches.iter().filter(|hatch| hatch.justification.is_none()).count()
+    }
+}
+
+impl AuditDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn format(&self, format: AuditFormat) -> String {
+        match format {
+            AuditFormat::Markdown => self.markdown(),
+            AuditFormat::Json => self.json(),
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let mut markdown = String::from("# Escape hatch changes\n\n");
+
+        if self.is_empty() {
+            markdown.push_str("No escape hatches added or removed.\n");
+            return markdown;
+        }
+
+        writeln!(markdown, "{} added, {} removed.", self.added.len(), self.removed.len()).unwrap();
+
+        for (title, hatches) in [("Added", &self.added), ("Removed", &self.removed)] {
+            if !hatches.is_empty() {
+                writeln!(markdown, "\n## {title}\n").unwrap();
+                markdown_table(&mut markdown, &self.root, hatches);
+            }
+        }
+
+        return markdown;
+    }
+
+    fn json(&self) -> String {
+        let added = self.added.iter().map(|hatch| json_hatch(&self.root, hatch, Some("added")));
+        let removed = self.removed.iter().map(|hatch| json_hatch(&self.root, hatch, Some("removed")));
+        return added.chain(removed).map(|line| format!("{line}\n")).collect();
+    }
+}
+
+fn uri(file: &Path) -> String {
+    file.to_string_lossy().replace('\\', "/")
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn markdown_code(code: &str) -> String {
+    match code.is_empty() {
+        true => String::new(),
+        false => format!("`{}`", markdown_cell(code)),
+    }
+}
+
+fn markdown_table(markdown: &mut String, root: &Path, hatches: &[EscapeHatch]) {
+    markdown.push_str("| Location | Function | Escape hatch | Arithmetic | Justification |\n");
+    markdown.push_str("| --- | --- | --- | --- | --- |\n");
+
+    for hatch in hatches {
+        let location = format!("{}:{}", uri(hatch.file.strip_prefix(root).unwrap_or(&hatch.file)), hatch.span.start);
+        let function = hatch.function.as_deref().map(markdown_code).unwrap_or_default();
+        let arithmetic: Vec<_> = hatch.arithmetic.iter().map(|expression| markdown_code(expression)).collect();
+        let justification = hatch.justification.as_deref().map(markdown_cell).unwrap_or("**none**".to_string());
+
+        writeln!(markdown, "| `{location}` | {function} | {} | {} | {justification} |", markdown_code(&hatch.code), arithmetic.join("<br>")).unwrap();
+    }
+}
+
+fn markdown_parse_errors(markdown: &mut String, root: &Path, errors: &[ParseError]) {
+    if errors.is_empty() {
+        return;
+    }
+
+    markdown.push_str("\n## Files that could not be audited\n\n");
+
+    for error in errors {
+        let location = error.location.map(|location| format!(":{location}")).unwrap_or_default();
+        writeln!(markdown, "- `{}{location}`: {}", uri(error.file.strip_prefix(root).unwrap_or(&error.file)), markdown_cell(&error.message)).unwrap();
+    }
+}
+
+fn json_hatch(root: &Path, hatch: &EscapeHatch, change: Option<&str>) -> serde_json::Value {
+    let mut line = serde_json::json!({
+        "type": "escape_hatch",
+        "file": uri(hatch.file.strip_prefix(root).unwrap_or(&hatch.file)),
+        "function": hatch.function,
+        "kind": hatch.kind.name(),
+        "code": hatch.code,
+        "arithmetic": hatch.arithmetic,
+        "justification": hatch.justification,
+        "line": hatch.span.start.line,
+        "column": hatch.span.start.column,
+        "end_line": hatch.span.end.line,
+        "end_column": hatch.span.end.column,
+    });
+
+    if let Some(change) = change {
+        line["change"] = change.into();
+    }
+
+    return line;
+}
+
+fn json_parse_error(root: &Path, error: &ParseError) -> serde_json::Value {
+    serde_json::json!({
+        "type": "parse_error",
+        "file": uri(error.file.strip_prefix(root).unwrap_or(&error.file)),
+        "message": error.message,
+        "line": error.location.map(|location| location.line),
+        "column": error.location.map(|location| location.column),
+    })
+}
+
+/// Lists the escape hatches in a file, or in the files under a directory the configuration includes.
+pub fn audit_path<P: AsRef<Path>>(root_path: P, config: &Config) -> Audit {
+    let root_path = root_path.as_ref();
+
+    let Some((root, files)) = crate::source_files(root_path, config) else {
+        let error = ParseError { file: root_path.to_path_buf(), location: None, message: "no such file or directory".to_string() };
+        return Audit { root: root_path.to_path_buf(), parse_errors: vec![error], ..Audit::default() };
+    };
+
+    let mut audit = Audit { root, ..Audit::default() };
+
+    for file in files {
+        let checked = crate::read_file(&file).and_then(|content| crate::check_file(&audit.root, &file, &content, config));
+        audit.add(checked);
+    }
+
+    audit.sort();
+    return audit;
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::report::{Report, Violation};
+
+const VERSION: u64 = 1;
+
+/// A violation recorded in a baseline. Entries are keyed by file, function and expression rather
+/// than by line, so unrelated edits that move the code around don't invalidate them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub file: String,
+    pub function: Option<String>,
+    pub expression: String,
+    /// How often the same expression occurs in the same function.
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    entries: BTreeMap<String, BaselineEntry>,
+}
+
+/// What `Baseline::filter` removed from a report.
+#[derive(Debug, Clone, Default)]
+pub struct BaselineStatus {
+    /// Violations that were already in the baseline.
+    pub known: usize,
+    /// Baseline entries that no longer occur, with the number of occurrences that went away.
+    pub fixed: Vec<BaselineEntry>,
+}
+
+// 64 bit FNV-1a, stable across platforms and releases unlike `std::hash`.
+fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn entry(report: &Report, violation: &Violation) -> BaselineEntry {
+    let file = report.relative_path(&violation.file).to_string_lossy().replace('\\', "/");
+    let expression: String = violation.expression.split_whitespace().collect();
+    let function = violation.function.clone();
+
+    let key = [file.as_str(), function.as_deref().unwrap_or_default(), expression.as_str()].join("\0");
+    let fingerprint = format!("{:016x}", fnv1a(key.bytes()));
+
+    BaselineEntry { fingerprint, file, function, expression, count: 1 }
+}
+
+impl Baseline {
+    /// Records every violation of the report.
+    pub fn from_report(report: &Report) -> Self {
+        let mut baseline = Baseline::default();
+
+        for violation in &report.violations {
+            let entry = entry(report, violation);
+            baseline.entries.entry(entry.fingerprint.clone()).and_modify(|known| known.count += 1).or_insert(entry);
+        }
+
+        return baseline;
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &BaselineEntry> {
+        self.entries.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.values().map(|entry| entry.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        let violations: Vec<_> = self.entries.values().map(|entry| serde_json::json!({
+            "fingerprint": entry.fingerprint,
+            "file": entry.file,
+            "function": entry.function,
+            "expression": entry.expression,
+            "count": entry.count,
+        })).collect();
+
+        let baseline = serde_json::json!({ "version": VERSION, "violations": violations });
+        return format!("{}\n", serde_json::to_string_pretty(&baseline).unwrap());
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let baseline: serde_json::Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+
+        if baseline["version"].as_u64() != Some(VERSION) {
+            return Err(format!("unsupported baseline version, expected {VERSION}"));
+        }
+
+        let violations = baseline["violations"].as_array().ok_or("missing `violations`")?;
+        let mut entries = BTreeMap::new();
+
+        for violation in violations {
+            let field = |name: &str| violation[name].as_str().map(str::to_string).ok_or(format!("missing `{name}` in a baseline entry"));
+
+            let entry = BaselineEntry {
+                fingerprint: field("fingerprint")?,
+                file: field("file")?,
+                function: violation["function"].as_str().map(str::to_string),
+                expression: field("expression")?,
+                count: violation["count"].as_u64().unwrap_or(1) as usize,
+            };
+
+            entries.insert(entry.fingerprint.clone(), entry);
+        }
+
+        return Ok(Baseline { entries });
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path.as_ref())?;
+
+        return Baseline::from_json(&json).map_err(|err| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, format!("invalid baseline {}: {err}", path.as_ref().display()))
+        });
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    /// Removes the violations recorded in the baseline from the report, so only new ones remain.
+    pub fn filter(&self, report: &mut Report) -> BaselineStatus {
+        let mut remaining: BTreeMap<&str, usize> = self.entries.iter().map(|(fingerprint, entry)| (fingerprint.as_str(), entry.count)).collect();
+        let mut status = BaselineStatus::default();
+
+        let violations = std::mem::take(&mut report.violations);
+
+        for violation in violations {
+            match remaining.get_mut(entry(report, &violation).fingerprint.as_str()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    status.known += 1;
+
+                    if let Some(stats) = report.files.iter_mut().find(|stats| stats.file == violation.file) {
+                        stats.violations -= 1;
+                    }
+                },
+                _ => report.violations.push(violation),
+            }
+        }
+
+        status.fixed = remaining.into_iter().filter(|(_, count)| *count > 0).map(|(fingerprint, count)| {
+            BaselineEntry { count, ..self.entries[fingerprint].clone() }
+        }).collect();
+
+        return status;
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "alloy-checked-math.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatArithmetic {
+    #[default]
+    Enforce,
+    /// Skip arithmetic on floats: float literals, `as f32`/`as f64` casts, `f32::`/`f64::` constants,
+    /// float returning functions like `f64::sqrt` or `x.abs()`, and variables declared as `f32` or
+    /// `f64` or initialized with one of these. The lint has no type inference, so floats it can't
+    /// see, like fields or function results, are still reported.
+    Ignore,
+}
+
+/// The arithmetic operators the lint knows about, compound assignments count as their operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Neg,
+}
+
+impl Operator {
+    pub const ALL: [Operator; 6] = [Operator::Add, Operator::Sub, Operator::Mul, Operator::Div, Operator::Rem, Operator::Neg];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operator::Add => "add",
+            Operator::Sub => "sub",
+            Operator::Mul => "mul",
+            Operator::Div => "div",
+            Operator::Rem => "rem",
+            Operator::Neg => "neg",
+        }
+    }
+}
+
+impl std::str::FromStr for Operator {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Operator::ALL.into_iter().find(|operator| operator.name() == name).ok_or_else(|| {
+            format!("unknown operator `{name}`, expected `add`, `sub`, `mul`, `div`, `rem` or `neg`")
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Severity {
+    /// Not reported at all.
+    Allow,
+    /// Reported without failing the check.
+    Warning,
+    #[default]
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Allow => "allow",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [Severity::Allow, Severity::Warning, Severity::Error].into_iter().find(|severity| severity.name() == name).ok_or_else(|| {
+            format!("unknown severity `{name}`, expected `allow`, `warning` or `error`")
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TestCode {
+    #[default]
+    Check,
+    /// Skip `#[test]` functions and `#[cfg(test)]` modules.
+    Skip,
+}
+
+/// The operators checked in the files matching `path`.
+#[derive(Debug, Clone)]
+pub struct PathOperators {
+    pub path: String,
+    pub operators: Vec<Operator>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub float_arithmetic: FloatArithmetic,
+    /// Globs of the files checked when a directory is checked, relative to `base`.
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub operators: Vec<Operator>,
+    /// Overrides `operators` for matching files, the last matching entry wins.
+    pub paths: Vec<PathOperators>,
+    /// Attributes that exempt a function, impl, trait or module, next to the checked ones.
+    pub exempt_attributes: Vec<String>,
+    /// Macros whose arguments are not checked, next to the checked ones.
+    pub exempt_macros: Vec<String>,
+    pub tests: TestCode,
+    pub severities: BTreeMap<Operator, Severity>,
+    /// The directory globs are relative to: the directory of the configuration file when it was
+    /// loaded from one, the checked directory otherwise.
+    pub base: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            float_arithmetic: FloatArithmetic::default(),
+            include: vec!["**/*.rs".to_string()],
+            exclude: Vec::new(),
+            operators: Operator::ALL.to_vec(),
+            paths: Vec::new(),
+            exempt_attributes: Vec::new(),
+            exempt_macros: Vec::new(),
+            tests: TestCode::default(),
+            severities: BTreeMap::new(),
+            base: None,
+        }
+    }
+}
+
+fn pattern(glob: &str) -> Option<glob::Pattern> {
+    glob::Pattern::new(glob.trim_start_matches("./")).ok()
+}
+
+fn matches(glob: &str, path: &Path) -> bool {
+    let options = glob::MatchOptions { require_literal_separator: true, ..glob::MatchOptions::default() };
+    pattern(glob).is_some_and(|pattern| pattern.matches_path_with(path, options))
+}
+
+fn strings(value: &toml::Value, key: &str) -> Result<Vec<String>, String> {
+    let values = value.as_array().ok_or(format!("`{key}` must be an array of strings"))?;
+    values.iter().map(|value| value.as_str().map(str::to_string).ok_or(format!("`{key}` must be an array of strings"))).collect()
+}
+
+fn string<'a>(value: &'a toml::Value, key: &str) -> Result<&'a str, String> {
+    value.as_str().ok_or(format!("`{key}` must be a string"))
+}
+
+fn operators(value: &toml::Value, key: &str) -> Result<Vec<Operator>, String> {
+    strings(value, key)?.iter().map(|name| name.parse()).collect()
+}
+
+impl Config {
+    pub fn with_float_arithmetic(mut self, float_arithmetic: FloatArithmetic) -> Self {
+        self.float_arithmetic = float_arithmetic;
+        self
+    }
+
+    /// Replaces the default `**/*.rs` on the first call.
+    pub fn with_include(mut self, glob: &str) -> Self {
+        if self.include == Config::default().include {
+            self.include.clear();
+        }
+
+        self.include.push(glob.to_string());
+        self
+    }
+
+    pub fn with_exclude(mut self, glob: &str) -> Self {
+        self.exclude.push(glob.to_string());
+        self
+    }
+
+    pub fn with_operators<I: IntoIterator<Item = Operator>>(mut self, operators: I) -> Self {
+        self.operators = operators.into_iter().collect();
+        self
+    }
+
+    pub fn with_path_operators<I: IntoIterator<Item = Operator>>(mut self, glob: &str, operators: I) -> Self {
+        self.paths.push(PathOperators { path: glob.to_string(), operators: operators.into_iter().collect() });
+        self
+    }
+
+    pub fn with_exempt_attribute(mut self, name: &str) -> Self {
+        self.exempt_attributes.push(name.to_string());
+        self
+    }
+
+    pub fn with_exempt_macro(mut self, name: &str) -> Self {
+        self.exempt_macros.push(name.to_string());
+        self
+    }
+
+    pub fn with_tests(mut self, tests: TestCode) -> Self {
+        self.tests = tests;
+        self
+    }
+
+    pub fn with_severity(mut self, operator: Operator, severity: Severity) -> Self {
+        self.severities.insert(operator, severity);
+        self
+    }
+
+    pub fn with_base<P: Into<PathBuf>>(mut self, base: P) -> Self {
+        self.base = Some(base.into());
+        self
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self, String> {
+        let table: toml::Table = source.parse().map_err(|err: toml::de::Error| err.message().to_string())?;
+        let mut config = Config::default();
+
+        for (key, value) in &table {
+            match key.as_str() {
+                "float-arithmetic" => config.float_arithmetic = match string(value, key)? {
+                    "enforce" => FloatArithmetic::Enforce,
+                    "ignore" => FloatArithmetic::Ignore,
+                    other => return Err(format!("unknown float arithmetic `{other}`, expected `enforce` or `ignore`")),
+                },
+                "include" => config.include = strings(value, key)?,
+                "exclude" => config.exclude = strings(value, key)?,
+                "operators" => config.operators = operators(value, key)?,
+                "exempt-attributes" => config.exempt_attributes = strings(value, key)?,
+                "exempt-macros" => config.exempt_macros = strings(value, key)?,
+                "tests" => config.tests = match string(value, key)? {
+                    "check" => TestCode::Check,
+                    "skip" => TestCode::Skip,
+                    other => return Err(format!("unknown test handling `{other}`, expected `check` or `skip`")),
+                },
+                "severity" => {
+                    let severities = value.as_table().ok_or("`severity` must be a table")?;
+
+                    for (operator, severity) in severities {
+                        config.severities.insert(operator.parse()?, string(severity, operator)?.parse()?);
+                    }
+                },
+                "paths" => {
+                    let paths = value.as_array().ok_or("`paths` must be an array of tables")?;
+
+                    for entry in paths {
+                        let path = string(entry.get("path").ok_or("missing `path` in `paths`")?, "path")?;
+                        let operators = operators(entry.get("operators").ok_or("missing `operators` in `paths`")?, "operators")?;
+                        config.paths.push(PathOperators { path: path.to_string(), operators });
+                    }
+                },
+                _ => return Err(format!("unknown key `{key}`")),
+            }
+        }
+
+        return Ok(config);
+    }
+
+    /// Loads a configuration file, whose globs are relative to its directory.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+
+        let config = Config::from_toml(&source).map_err(|err| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, format!("invalid {}: {err}", path.display()))
+        })?;
+
+        return Ok(config.with_base(path.parent().unwrap_or(Path::new("."))));
+    }
+
+    /// Loads the nearest `alloy-checked-math.toml` in `start` or its parents, up to the workspace
+    /// or repository root, and falls back to the default configuration.
+    pub fn discover<P: AsRef<Path>>(start: P) -> std::io::Result<Self> {
+        let start = std::path::absolute(start.as_ref())?;
+
+        for dir in start.ancestors().filter(|dir| dir.is_dir()) {
+            let path = dir.join(CONFIG_FILE);
+
+            if path.is_file() {
+                return Config::load(path);
+            }
+
+            let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).unwrap_or_default();
+
+            if manifest.lines().any(|line| line.trim() == "[workspace]") || dir.join(".git").exists() {
+                break;
+            }
+        }
+
+        return Ok(Config::default());
+    }
+
+    pub(crate) fn is_included(&self, relative_path: &Path) -> bool {
+        self.include.iter().any(|glob| matches(glob, relative_path)) && !self.exclude.iter().any(|glob| matches(glob, relative_path))
+    }
+
+    pub(crate) fn operators_for(&self, relative_path: &Path) -> &[Operator] {
+        match self.paths.iter().rfind(|paths| matches(&paths.path, relative_path)) {
+            Some(paths) => &paths.operators,
+            None => &self.operators,
+        }
+    }
+
+    pub(crate) fn severity(&self, operator: Operator) -> Severity {
+        self.severities.get(&operator).copied().unwrap_or_default()
+    }
+
+    // Globs match paths relative to `base`, or to the checked root for files outside of it.
+    pub(crate) fn relative_path(&self, root: &Path, file: &Path) -> PathBuf {
+        let absolute = |path: &Path| std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+
+        if let Some(base) = &self.base && let Ok(relative) = absolute(file).strip_prefix(absolute(base)) {
+            return relative.to_path_buf();
+        }
+
+        return file.strip_prefix(root).unwrap_or(file).to_path_buf();
+    }
+}
@@ -0,0 +1,335 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+
+use crate::report::{Location, SourceSpan};
+use crate::Config;
+
+const DIFF_CONTEXT: usize = 3;
+
+// Macros whose arguments `#[checked_fn]` rewrites, as in the macro crate. Arithmetic in the
+// arguments of any other macro has to be wrapped in `checked!` itself.
+const REWRITTEN_MACROS: &[&str] = &[
+    "vec", "matches",
+    "assert", "assert_eq", "assert_ne", "debug_assert", "debug_assert_eq", "debug_assert_ne", "dbg",
+    "format", "format_args", "print", "println", "eprint", "eprintln", "write", "writeln", "panic",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FixPolicy {
+    /// Add `#[checked_fn]` to functions returning `Result`, wrap their arithmetic inside macros it
+    /// doesn't rewrite in `checked!`, and the remaining expressions in `unchecked!`.
+    #[default]
+    CheckedFn,
+    /// Wrap expressions in functions returning `Result` in `checked!`, and the remaining ones in
+    /// `unchecked!`.
+    CheckedMacro,
+    /// Wrap every expression in `unchecked!`, which keeps the behaviour but makes it explicit.
+    UncheckedMacro,
+}
+
+impl std::str::FromStr for FixPolicy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            "checked-fn" => Ok(FixPolicy::CheckedFn),
+            "checked-macro" => Ok(FixPolicy::CheckedMacro),
+            "unchecked-macro" => Ok(FixPolicy::UncheckedMacro),
+            _ => Err(format!("unknown fix policy `{name}`, expected `checked-fn`, `checked-macro` or `unchecked-macro`")),
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FixOptions {
+    pub policy: FixPolicy,
+    /// The path the inserted attributes and macros are qualified with.
+    pub namespace: String,
+    /// Compute the fix without writing it back.
+    pub dry_run: bool,
+}
+
+impl Default for FixOptions {
+    fn default() -> Self {
+        FixOptions { policy: FixPolicy::default(), namespace: "alloy_checked_math".to_string(), dry_run: false }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Fix {
+    pub file: PathBuf,
+    pub original: String,
+    pub fixed: String,
+    /// Functions that were annotated with `#[checked_fn]`.
+    pub checked_fns: usize,
+    /// Expressions that were wrapped in `checked!` or `unchecked!`.
+    pub wrapped: usize,
+    insertions: Vec<Insertion>,
+}
+
+#[derive(Debug, Clone)]
+struct Insertion {
+    offset: usize,
+    text: String,
+}
+
+#[derive(Clone, Copy)]
+enum ScopeKind {
+    // Functions returning `Result` can propagate errors, `start` is their first token, where
+    // `#[checked_fn]` goes.
+    Fn { returns_result: bool, start: Location },
+    // Closures and async blocks would return the error from themselves instead.
+    Nested,
+}
+
+struct Scope {
+    span: SourceSpan,
+    kind: ScopeKind,
+}
+
+#[derive(Default)]
+struct ScopeVisitor {
+    scopes: Vec<Scope>,
+    /// Invocations of macros whose arguments `#[checked_fn]` leaves alone.
+    opaque_macros: Vec<SourceSpan>,
+}
+
+// The lint has no type information, so any return type named `Result` is assumed to have an error
+// type that implements `From<CheckedMathError>`.
+fn returns_result(sig: &syn::Signature) -> bool {
+    match &sig.output {
+        syn::ReturnType::Type(_, ty) => matches!(&**ty,
+            syn::Type::Path(ty) if ty.path.segments.last().is_some_and(|segment| segment.ident == "Result")
+        ),
+        syn::ReturnType::Default => false,
+    }
+}
+
+impl ScopeVisitor {
+    fn push_fn(&mut self, vis: &syn::Visibility, sig: &syn::Signature, block: &syn::Block) {
+        let start = match vis {
+            syn::Visibility::Inherited => sig.span(),
+            vis => vis.span(),
+        };
+
+        let kind = ScopeKind::Fn { returns_result: returns_result(sig), start: start.start().into() };
+        self.scopes.push(Scope { span: block.span().into(), kind });
+    }
+}
+
+impl<'ast> Visit<'ast> for ScopeVisitor {
+    fn visit_item_fn(&mut self, i: &'ast syn::ItemFn) {
+        self.push_fn(&i.vis, &i.sig, &i.block);
+        syn::visit::visit_item_fn(self, i);
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast syn::ImplItemFn) {
+        self.push_fn(&i.vis, &i.sig, &i.block);
+        syn::visit::visit_impl_item_fn(self, i);
+    }
+
+    fn visit_trait_item_fn(&mut self, i: &'ast syn::TraitItemFn) {
+        if let Some(block) = &i.default {
+            self.push_fn(&syn::Visibility::Inherited, &i.sig, block);
+        }
+
+        syn::visit::visit_trait_item_fn(self, i);
+    }
+
+    fn visit_expr_closure(&mut self, i: &'ast syn::ExprClosure) {
+        self.scopes.push(Scope { span: i.span().into(), kind: ScopeKind::Nested });
+        syn::visit::visit_expr_closure(self, i);
+    }
+
+    fn visit_expr_async(&mut self, i: &'ast syn::ExprAsync) {
+        self.scopes.push(Scope { span: i.span().into(), kind: ScopeKind::Nested });
+        syn::visit::visit_expr_async(self, i);
+    }
+
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        if !i.path.segments.last().is_some_and(|segment| REWRITTEN_MACROS.contains(&segment.ident.to_string().as_str())) {
+            self.opaque_macros.push(i.span().into());
+        }
+
+        crate::visitor::macro_exprs(i).iter().for_each(|expr| self.visit_expr(expr));
+    }
+}
+
+fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0).chain(content.match_indices('\n').map(|(offset, _)| offset + 1)).collect()
+}
+
+// Columns count characters, so they are mapped to byte offsets through the line.
+fn byte_offset(content: &str, line_starts: &[usize], location: Location) -> usize {
+    let start = line_starts[location.line - 1];
+    let line = &content[start..];
+    return start + line.char_indices().nth(location.column - 1).map_or(line.len(), |(offset, _)| offset);
+}
+
+fn insert(content: &str, insertions: &[Insertion]) -> String {
+    let mut fixed = String::with_capacity(content.len());
+    let mut copied = 0;
+
+    for insertion in insertions {
+        fixed.push_str(&content[copied..insertion.offset]);
+        fixed.push_str(&insertion.text);
+        copied = insertion.offset;
+    }
+
+    fixed.push_str(&content[copied..]);
+    return fixed;
+}
+
+pub(crate) fn fix_source(root: &Path, file: &Path, content: &str, config: &Config, options: &FixOptions) -> Result<Fix, crate::ParseError> {
+    let violations = crate::check_file(root, file, content, config)?.violations;
+    let mut scopes = ScopeVisitor::default();
+    scopes.visit_file(&syn::parse_file(content).unwrap());
+
+    let line_starts = line_starts(content);
+    let namespace = &options.namespace;
+    let mut insertions = Vec::new();
+    let mut checked_fns = Vec::new();
+    let mut wrapped = 0;
+
+    for violation in &violations {
+        let SourceSpan { start, end } = violation.expression_span;
+
+        // Scopes are recorded outside in, so the last one containing the expression is the innermost.
+        let scope = scopes.scopes.iter().rfind(|scope| scope.span.start <= start && end <= scope.span.end).map(|scope| scope.kind);
+        let propagates = matches!(scope, Some(ScopeKind::Fn { returns_result: true, .. }));
+        let in_opaque_macro = scopes.opaque_macros.iter().any(|span| span.start <= start && end <= span.end);
+
+        match (options.policy, scope) {
+            (FixPolicy::CheckedFn, Some(ScopeKind::Fn { returns_result: true, start: fn_start })) if !in_opaque_macro => {
+                if !checked_fns.contains(&fn_start) {
+                    checked_fns.push(fn_start);
+
+                    // On its own line above the function, or right before it when it shares its line.
+                    let line_start = line_starts[fn_start.line - 1];
+                    let offset = byte_offset(content, &line_starts, fn_start);
+                    let indent = &content[line_start..offset];
+
+                    insertions.push(match indent.trim().is_empty() {
+                        true => Insertion { offset: line_start, text: format!("{indent}#[{namespace}::checked_fn]\n") },
+                        false => Insertion { offset, text: format!("#[{namespace}::checked_fn] ") },
+                    });
+                }
+            },
+            // `#[checked_fn]` doesn't reach into the arguments of other macros, so they are wrapped in
+            // `checked!` under either checked policy.
+            (policy, _) => {
+                let name = if policy != FixPolicy::UncheckedMacro && propagates { "checked" } else { "unchecked" };
+
+                insertions.push(Insertion { offset: byte_offset(content, &line_starts, start), text: format!("{namespace}::{name}! {{ ") });
+                insertions.push(Insertion { offset: byte_offset(content, &line_starts, end), text: " }".to_string() });
+                wrapped += 1;
+            },
+        }
+    }
+
+    insertions.sort_by_key(|insertion| insertion.offset);
+
+    return Ok(Fix {
+        file: file.to_path_buf(),
+        original: content.to_string(),
+        fixed: insert(content, &insertions),
+        checked_fns: checked_fns.len(),
+        wrapped,
+        insertions,
+    });
+}
+
+/// Rewrites the unchecked arithmetic in a file according to `options.policy` and writes it back,
+/// unless `options.dry_run` is set. Only the flagged expressions and annotated functions change.
+pub fn fix_file<P: AsRef<Path>>(path: P, config: &Config, options: &FixOptions) -> std::io::Result<Fix> {
+    let path = path.as_ref();
+    return fix_file_in(path.parent().unwrap_or(Path::new("")), path, config, options);
+}
+
+fn fix_file_in(root: &Path, path: &Path, config: &Config, options: &FixOptions) -> std::io::Result<Fix> {
+    let content = std::fs::read_to_string(path)?;
+
+    let fix = fix_source(root, path, &content, config, options).map_err(|error| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, format!("failed to parse {}: {}", path.display(), error.message))
+    })?;
+
+    if !options.dry_run && fix.is_changed() {
+        std::fs::write(path, &fix.fixed)?;
+    }
+
+    return Ok(fix);
+}
+
+/// Runs `fix_file` on a file, or on every `.rs` file under a directory.
+pub fn fix_path<P: AsRef<Path>>(root_path: P, config: &Config, options: &FixOptions) -> std::io::Result<Vec<Fix>> {
+    let (root, files) = crate::source_files(root_path.as_ref(), config).ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} does not exist", root_path.as_ref().display()))
+    })?;
+
+    return files.iter().map(|file| fix_file_in(&root, file, config, options)).collect();
+}
+
+impl Fix {
+    pub fn is_changed(&self) -> bool {
+        self.original != self.fixed
+    }
+
+    /// A unified diff from the original to the fixed source.
+    pub fn diff(&self) -> String {
+        let mut diff = String::new();
+
+        if !self.is_changed() {
+            return diff;
+        }
+
+        let lines: Vec<&str> = self.original.split_inclusive('\n').collect();
+        let line_starts = line_starts(&self.original);
+        let line_of = |offset: usize| (line_starts.partition_point(|&start| start <= offset) - 1).min(lines.len() - 1);
+
+        // Every change is an insertion, so each edited line of the original becomes one or more
+        // lines of the fixed source and all other lines stay as they are.
+        let mut changed: Vec<(usize, Vec<String>)> = Vec::new();
+
+        for group in self.insertions.chunk_by(|a, b| line_of(a.offset) == line_of(b.offset)) {
+            let line = line_of(group[0].offset);
+            let shifted: Vec<_> = group.iter().map(|insertion| Insertion { offset: insertion.offset - line_starts[line], text: insertion.text.clone() }).collect();
+            let fixed = insert(lines[line], &shifted);
+            changed.push((line, fixed.split_inclusive('\n').map(str::to_string).collect()));
+        }
+
+        let path = self.file.to_string_lossy().replace('\\', "/");
+        writeln!(diff, "--- a/{path}").unwrap();
+        writeln!(diff, "+++ b/{path}").unwrap();
+
+        let mut added = 0;
+
+        for hunk in changed.chunk_by(|(a, _), (b, _)| b - a <= 2 * DIFF_CONTEXT) {
+            let before = hunk[0].0.saturating_sub(DIFF_CONTEXT);
+            let after = (hunk[hunk.len() - 1].0 + DIFF_CONTEXT + 1).min(lines.len());
+            let extra: usize = hunk.iter().map(|(_, fixed)| fixed.len() - 1).sum();
+
+            writeln!(diff, "@@ -{},{} +{},{} @@", before + 1, after - before, before + added + 1, after - before + extra).unwrap();
+            added += extra;
+
+            for (line, text) in lines.iter().enumerate().take(after).skip(before) {
+                match hunk.iter().find(|(changed, _)| *changed == line) {
+                    // Lines inserted above an unchanged line, like `#[checked_fn]`, are pure additions.
+                    Some((_, fixed)) if fixed.last().is_some_and(|last| last == text) => {
+                        fixed[..fixed.len() - 1].iter().for_each(|fixed| writeln!(diff, "+{}", fixed.trim_end_matches('\n')).unwrap());
+                        writeln!(diff, " {}", text.trim_end_matches('\n')).unwrap();
+                    },
+                    Some((_, fixed)) => {
+                        writeln!(diff, "-{}", text.trim_end_matches('\n')).unwrap();
+                        fixed.iter().for_each(|fixed| writeln!(diff, "+{}", fixed.trim_end_matches('\n')).unwrap());
+                    },
+                    None => writeln!(diff, " {}", text.trim_end_matches('\n')).unwrap(),
+                }
+            }
+        }
+
+        return diff;
+    }
+}
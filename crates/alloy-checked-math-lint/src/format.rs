@@ -0,0 +1,271 @@
+use std::fmt::Write;
+
+use crate::config::Severity;
+use crate::report::{FileStats, ParseError, Report, SourceSpan, Violation};
+
+const TOOL: &str = "alloy-checked-math-lint";
+const UNCHECKED_RULE: &str = "unchecked-arithmetic";
+const PARSE_RULE: &str = "parse-error";
+const UNJUSTIFIED_RULE: &str = "unjustified-suppression";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// The human readable report printed by `assert_checked`.
+    #[default]
+    Text,
+    /// One JSON object per violation, suppression or parse error.
+    Json,
+    /// A SARIF 2.1.0 log for code scanning.
+    Sarif,
+    /// JUnit XML with one test case per checked file.
+    Junit,
+    /// GitHub Actions `::error` and `::warning` workflow commands.
+    Github,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return match name {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "sarif" => Ok(Format::Sarif),
+            "junit" => Ok(Format::Junit),
+            "github" => Ok(Format::Github),
+            _ => Err(format!("unknown format `{name}`, expected `text`, `json`, `sarif`, `junit` or `github`")),
+        };
+    }
+}
+
+// Paths relative to the report's root, with forward slashes on every platform.
+fn uri(report: &Report, file: &std::path::Path) -> String {
+    report.relative_path(file).to_string_lossy().replace('\\', "/")
+}
+
+fn json(report: &Report) -> String {
+    let violations = report.violations.iter().map(|violation| serde_json::json!({
+        "type": "violation",
+        "file": uri(report, &violation.file),
+        "function": violation.function,
+        "expression": violation.expression,
+        "kind": violation.kind.name(),
+        "severity": violation.severity.name(),
+        "message": violation.message(),
+        "line": violation.span.start.line,
+        "column": violation.span.start.column,
+        "end_line": violation.span.end.line,
+        "end_column": violation.span.end.column,
+    }));
+
+    let suppressions = report.suppressions.iter().map(|suppression| serde_json::json!({
+        "type": "suppression",
+        "file": uri(report, &suppression.file),
+        "function": suppression.function,
+        "kind": suppression.kind.name(),
+        "reason": suppression.reason,
+        "justified": suppression.is_justified(),
+        "suppressed": suppression.violations.iter().map(|violation| violation.expression.as_str()).collect::<Vec<_>>(),
+        "message": suppression.message(),
+        "line": suppression.span.start.line,
+        "column": suppression.span.start.column,
+        "end_line": suppression.span.end.line,
+        "end_column": suppression.span.end.column,
+    }));
+
+    let parse_errors = report.parse_errors.iter().map(|error| serde_json::json!({
+        "type": "parse_error",
+        "file": uri(report, &error.file),
+        "message": error.message,
+        "line": error.location.map(|location| location.line),
+        "column": error.location.map(|location| location.column),
+    }));
+
+    return violations.chain(suppressions).chain(parse_errors).map(|line| format!("{line}\n")).collect();
+}
+
+// Parse errors only have a start location, which is reported as an empty span.
+fn parse_error_span(error: &ParseError) -> Option<SourceSpan> {
+    error.location.map(|location| SourceSpan { start: location, end: location })
+}
+
+fn sarif_result(report: &Report, rule: &str, severity: Severity, file: &std::path::Path, span: Option<SourceSpan>, message: String) -> serde_json::Value {
+    let mut result = serde_json::json!({
+        "ruleId": rule,
+        "level": severity.name(),
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": { "artifactLocation": { "uri": uri(report, file) } },
+        }],
+    });
+
+    if let Some(SourceSpan { start, end }) = span {
+        let mut region = serde_json::json!({ "startLine": start.line, "startColumn": start.column });
+
+        if end != start {
+            region["endLine"] = end.line.into();
+            region["endColumn"] = end.column.into();
+        }
+
+        result["locations"][0]["physicalLocation"]["region"] = region;
+    }
+
+    return result;
+}
+
+fn sarif_violation(report: &Report, violation: &Violation) -> serde_json::Value {
+    let mut result = sarif_result(report, UNCHECKED_RULE, violation.severity, &violation.file, Some(violation.span), violation.message());
+
+    if let Some(function) = &violation.function {
+        result["locations"][0]["logicalLocations"] = serde_json::json!([{ "name": function, "kind": "function" }]);
+    }
+
+    return result;
+}
+
+// Suppressed violations are results with an in-source suppression, so code scanning shows them as
+// dismissed along with their justification.
+fn sarif(report: &Report) -> String {
+    let mut results: Vec<_> = report.violations.iter().map(|violation| sarif_violation(report, violation)).collect();
+
+    for suppression in &report.suppressions {
+        results.extend(suppression.violations.iter().map(|violation| {
+            let mut result = sarif_violation(report, violation);
+            result["suppressions"] = serde_json::json!([{ "kind": "inSource", "justification": suppression.reason }]);
+            result
+        }));
+    }
+
+    results.extend(report.unjustified().map(|suppression| {
+        sarif_result(report, UNJUSTIFIED_RULE, Severity::Error, &suppression.file, Some(suppression.span), suppression.message())
+    }));
+
+    results.extend(report.parse_errors.iter().map(|error| sarif_result(report, PARSE_RULE, Severity::Error, &error.file, parse_error_span(error), error.message.clone())));
+
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": [
+                        {
+                            "id": UNCHECKED_RULE,
+                            "shortDescription": { "text": "Arithmetic that can overflow, underflow or divide by zero without being checked" },
+                        },
+                        {
+                            "id": UNJUSTIFIED_RULE,
+                            "shortDescription": { "text": "Unchecked arithmetic allowed without a reason" },
+                        },
+                        {
+                            "id": PARSE_RULE,
+                            "shortDescription": { "text": "Source file that could not be read or parsed" },
+                        },
+                    ],
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    return format!("{}\n", serde_json::to_string_pretty(&log).unwrap());
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Warnings don't fail a test case, suppressions without a reason do.
+fn junit(report: &Report) -> String {
+    let failed = |stats: &&FileStats| false
+        || report.errors().any(|violation| violation.file == stats.file)
+        || report.unjustified().any(|suppression| suppression.file == stats.file);
+    let tests = report.files.len() + report.parse_errors.len();
+    let failures = report.files.iter().filter(failed).count();
+    let errors = report.parse_errors.len();
+
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(xml, r#"<testsuites name="{TOOL}" tests="{tests}" failures="{failures}" errors="{errors}">"#).unwrap();
+    writeln!(xml, r#"  <testsuite name="{UNCHECKED_RULE}" tests="{tests}" failures="{failures}" errors="{errors}">"#).unwrap();
+
+    for stats in &report.files {
+        let name = xml_escape(&uri(report, &stats.file));
+
+        if !failed(&stats) {
+            writeln!(xml, r#"    <testcase name="{name}" classname="{UNCHECKED_RULE}"/>"#).unwrap();
+            continue;
+        }
+
+        let violations: Vec<_> = report.errors().filter(|violation| violation.file == stats.file).map(|violation| (violation.span, violation.message())).collect();
+        let unjustified: Vec<_> = report.unjustified().filter(|suppression| suppression.file == stats.file).map(|suppression| (suppression.span, suppression.message())).collect();
+
+        let details: String = violations.iter().chain(&unjustified).map(|(span, message)| {
+            format!("{}:{}: {}\n", xml_escape(&uri(report, &stats.file)), span.start, xml_escape(message))
+        }).collect();
+
+        let mut summary = format!("found {} unchecked arithmetic expressions", violations.len());
+
+        if !unjustified.is_empty() {
+            write!(summary, " and {} suppressions without a reason", unjustified.len()).unwrap();
+        }
+
+        writeln!(xml, r#"    <testcase name="{name}" classname="{UNCHECKED_RULE}">"#).unwrap();
+        writeln!(xml, r#"      <failure type="{UNCHECKED_RULE}" message="{summary}">{details}</failure>"#).unwrap();
+        writeln!(xml, r#"    </testcase>"#).unwrap();
+    }
+
+    for ParseError { file, location, message } in &report.parse_errors {
+        let location = location.map(|location| format!("{}:{location}: ", uri(report, file))).unwrap_or_default();
+        writeln!(xml, r#"    <testcase name="{}" classname="{UNCHECKED_RULE}">"#, xml_escape(&uri(report, file))).unwrap();
+        writeln!(xml, r#"      <error type="{PARSE_RULE}" message="{}"/>"#, xml_escape(&format!("{location}{message}"))).unwrap();
+        writeln!(xml, r#"    </testcase>"#).unwrap();
+    }
+
+    writeln!(xml, "  </testsuite>").unwrap();
+    writeln!(xml, "</testsuites>").unwrap();
+
+    return xml;
+}
+
+// https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions#setting-an-error-message
+fn github_escape(text: &str, property: bool) -> String {
+    let escaped = text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A");
+
+    match property {
+        true => escaped.replace(':', "%3A").replace(',', "%2C"),
+        false => escaped,
+    }
+}
+
+fn github(report: &Report) -> String {
+    let violations = report.violations.iter().map(|violation| {
+        (violation.severity, &violation.file, Some(violation.span), "Unchecked arithmetic", violation.message())
+    });
+    let unjustified = report.unjustified().map(|suppression| {
+        (Severity::Error, &suppression.file, Some(suppression.span), "Unjustified suppression", suppression.message())
+    });
+    let parse_errors = report.parse_errors.iter().map(|error| (Severity::Error, &error.file, parse_error_span(error), "Parse error", error.message.clone()));
+
+    return violations.chain(unjustified).chain(parse_errors).map(|(severity, file, span, title, message)| {
+        let location = span.map(|SourceSpan { start, end }| {
+            format!(",line={},col={},endLine={},endColumn={}", start.line, start.column, end.line, end.column)
+        }).unwrap_or_default();
+
+        format!("::{} file={}{location},title={}::{}\n", severity.name(), github_escape(&uri(report, file), true), github_escape(title, true), github_escape(&message, false))
+    }).collect();
+}
+
+impl Report {
+    pub fn format(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.to_string(),
+            Format::Json => json(self),
+            Format::Sarif => sarif(self),
+            Format::Junit => junit(self),
+            Format::Github => github(self),
+        }
+    }
+}
@@ -1,166 +1,133 @@
-use std::vec;
+mod audit;
+mod baseline;
+mod config;
+mod fix;
+mod format;
+mod report;
+mod visitor;
 
 use glob::glob;
 use syn::visit::Visit;
 
-fn is_checked_binary_op(op: syn::BinOp) -> bool {
-    matches!(op,
-        | syn::BinOp::Add(_) | syn::BinOp::AddAssign(_)
-        | syn::BinOp::Sub(_) | syn::BinOp::SubAssign(_)
-        | syn::BinOp::Mul(_) | syn::BinOp::MulAssign(_)
-        | syn::BinOp::Div(_) | syn::BinOp::DivAssign(_)
-        | syn::BinOp::Rem(_) | syn::BinOp::RemAssign(_)
-    )
-}
+pub use audit::{audit_path, Audit, AuditDiff, AuditFormat, EscapeHatch, EscapeHatchKind};
+pub use baseline::{Baseline, BaselineEntry, BaselineStatus};
+pub use config::{Config, FloatArithmetic, Operator, PathOperators, Severity, TestCode, CONFIG_FILE};
+pub use fix::{fix_file, fix_path, Fix, FixOptions, FixPolicy};
+pub use format::Format;
+pub use report::{FileStats, Location, ParseError, Report, SourceSpan, Suppression, SuppressionKind, Violation, ViolationKind};
 
-fn is_checked_unary_op(op: syn::UnOp) -> bool {
-    matches!(op,
-        | syn::UnOp::Neg(_)
-    )
+/// Checks with the nearest `alloy-checked-math.toml`, see [`Config::discover`].
+pub fn assert_checked<P: AsRef<std::path::Path>>(root_path: P) {
+    let config = Config::discover(&root_path).expect("Failed to load the lint configuration");
+    assert_checked_with_config(root_path, &config);
 }
 
-struct Error {
-    pub current_file: std::path::PathBuf,
-    pub current_fn: Option<syn::Ident>,
-    pub unchecked_expr: syn::Expr,
-}
+pub fn assert_checked_with_config<P: AsRef<std::path::Path>>(root_path: P, config: &Config) {
+    assert!(root_path.as_ref().exists(), "Root path does not exist");
 
-struct CheckedVisitor {
-    pub current_file: std::path::PathBuf,
-    pub current_fn: Option<syn::Ident>,
-    pub errors: Vec<Error>,
-}
+    let report = check_path(root_path, config);
+    print!("{report}");
 
-impl CheckedVisitor {
-    fn push_error(&mut self, unchecked_expr: syn::Expr) {
-        self.errors.push(Error {
-            current_file: self.current_file.clone(),
-            current_fn: self.current_fn.clone(),
-            unchecked_expr,
-        });
-    }
+    assert!(report.parse_errors.is_empty(), "Failed to check the codebase.");
+    assert!(report.errors().next().is_none(), "Unchecked arithmetic expressions found in the codebase.");
+    assert!(report.unjustified().next().is_none(), "Unchecked arithmetic allowed without a reason.");
 }
 
-fn has_checked_fn_attr(attrs: &[syn::Attribute]) -> bool {
-    attrs.iter().any(|attr| false
-        || attr.path().is_ident("checked_fn")
-        || attr.path().is_ident("unchecked_fn")
-    )
-}
-
-impl<'ast> Visit<'ast> for CheckedVisitor {
-    fn visit_item_fn(&mut self, i: &'ast syn::ItemFn) {
-        if has_checked_fn_attr(&i.attrs) {
-            return;
-        }
-
-        self.current_fn = Some(i.sig.ident.clone());
-        syn::visit::visit_block(self, &i.block);
-        self.current_fn = None;
-    }
-
-    fn visit_impl_item_fn(&mut self, i: &'ast syn::ImplItemFn) {
-        if has_checked_fn_attr(&i.attrs) {
-            return;
-        }
+// `pool::math` for `pool/math.rs` and `pool/math/mod.rs`, nothing for a crate root.
+fn module_path(relative_path: &std::path::Path) -> Vec<String> {
+    let mut module: Vec<String> = relative_path.with_extension("").iter().map(|name| name.to_string_lossy().into_owned()).collect();
 
-        self.current_fn = Some(i.sig.ident.clone());
-        syn::visit::visit_block(self, &i.block);
-        self.current_fn = None;
+    if module.last().is_some_and(|name| name == "mod") || module == ["lib"] || module == ["main"] {
+        module.pop();
     }
 
-    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
-        if is_checked_binary_op(node.op) {
-            return self.push_error(syn::Expr::Binary(node.clone()));
-        }
-
-        self.visit_expr(&node.left);
-        self.visit_expr(&node.right);
-    }
-
-    fn visit_expr_unary(&mut self, node: &'ast syn::ExprUnary) {
-        if is_checked_unary_op(node.op) {
-            return self.push_error(syn::Expr::Unary(node.clone()));
-        }
-
-        self.visit_expr(&node.expr);
-    }
+    return module;
 }
 
-fn pretty_expr(expr: &syn::Expr) -> String {
-    let file = syn::File {
-        shebang: None,
-        attrs: vec![],
-        items: vec![syn::parse_quote! {
-            fn __checked_expr() {#expr}
-        }],
-    };
-
-    let unparsed = prettyplease::unparse(&file);
+// Everything found in one file.
+#[derive(Debug)]
+pub(crate) struct FileCheck {
+    pub stats: FileStats,
+    pub violations: Vec<Violation>,
+    pub suppressions: Vec<Suppression>,
+    pub escape_hatches: Vec<EscapeHatch>,
+}
 
-    return unparsed
-        .trim()
-        .strip_prefix("fn __checked_expr() {").unwrap()
-        .strip_suffix("}").unwrap()
-        .trim()
-        .to_string();
+pub(crate) fn read_file(file: &std::path::Path) -> Result<String, ParseError> {
+    std::fs::read_to_string(file).map_err(|err| ParseError { file: file.to_path_buf(), location: None, message: err.to_string() })
 }
 
-pub fn assert_checked<P: AsRef<std::path::Path>>(root_path: P) {
-    let mut root_path = root_path.as_ref();
+// Checks a file under `root`, which its module path and the configured globs are relative to.
+pub(crate) fn check_file(root: &std::path::Path, file: &std::path::Path, content: &str, config: &Config) -> Result<FileCheck, ParseError> {
+    let source = syn::parse_file(content).map_err(|err| ParseError {
+        file: file.to_path_buf(),
+        location: Some(err.span().start().into()),
+        message: err.to_string(),
+    })?;
+
+    let module = module_path(file.strip_prefix(root).unwrap_or(file));
+    let operators = config.operators_for(&config.relative_path(root, file));
+    let mut visitor = visitor::CheckedVisitor::new(config, operators, file.to_path_buf(), content, module);
+    visitor.visit_file(&source);
+
+    return Ok(FileCheck {
+        stats: visitor.stats,
+        violations: visitor.violations,
+        suppressions: visitor.suppressions,
+        escape_hatches: visitor.escape_hatches,
+    });
+}
 
-    assert!(root_path.exists(), "Root path does not exist");
+// The root that paths are reported relative to, and the files to check under it. A file passed
+// explicitly is always checked, files found in a directory only if the configuration includes them.
+pub(crate) fn source_files(root_path: &std::path::Path, config: &Config) -> Option<(std::path::PathBuf, Vec<std::path::PathBuf>)> {
+    if root_path.is_file() {
+        return Some((root_path.parent().unwrap().to_path_buf(), vec![root_path.to_path_buf()]));
+    }
 
-    let files = if root_path.is_file() {
-        let files = vec![root_path.to_path_buf()];
-        root_path = root_path.parent().unwrap();
-        files
-    } else {
-        glob(root_path.join("**/*.rs").to_str().unwrap())
+    if root_path.is_dir() {
+        let files = glob(root_path.join("**/*.rs").to_str().unwrap())
             .expect("Failed to read glob pattern")
             .filter_map(Result::ok)
-            .collect()
-    };
+            .filter(|file| config.is_included(&config.relative_path(root_path, file)))
+            .collect();
 
-    let mut errors = Vec::new();
+        return Some((root_path.to_path_buf(), files));
+    }
 
-    for path in files {
-        print!("Checking {} ... ", path.strip_prefix(root_path).unwrap().display());
+    return None;
+}
 
-        let mut visitor = CheckedVisitor { current_file: path.clone(), current_fn: None, errors: Vec::new() };
-        let content = std::fs::read_to_string(&path).unwrap();
-        let source = syn::parse_str(&content).unwrap();
+/// Checks a file, or every `.rs` file under a directory, without printing or panicking.
+pub fn check_path<P: AsRef<std::path::Path>>(root_path: P, config: &Config) -> Report {
+    let root_path = root_path.as_ref();
 
-        visitor.visit_file(&source);
+    let Some((root, files)) = source_files(root_path, config) else {
+        let error = ParseError { file: root_path.to_path_buf(), location: None, message: "no such file or directory".to_string() };
+        return Report { root: root_path.to_path_buf(), parse_errors: vec![error], ..Report::default() };
+    };
 
-        if visitor.errors.is_empty() {
-            println!("done");
-        } else {
-            println!("found {} unchecked arithmetic expressions", visitor.errors.len());
-        }
+    let mut report = Report { root, ..Report::default() };
 
-        errors.extend(visitor.errors);
-    }
+    for file in files {
+        let checked = read_file(&file).and_then(|content| check_file(&report.root, &file, &content, config));
 
-    if errors.is_empty() {
-        println!("No unchecked arithmetic expressions found in the codebase.");
-    } else {
-        println!("Found total {} unchecked arithmetic expressions", errors.len());
-        println!("");
-        for error in &errors {
-            println!("  - path: {}", error.current_file.strip_prefix(root_path).unwrap().display());
-            println!("    function: {}", error.current_fn.as_ref().map(|fn_name| fn_name.to_string()).unwrap_or("unknown".to_string()));
-            println!("    expression: {}", pretty_expr(&error.unchecked_expr));
-            println!("");
+        match checked {
+            Ok(checked) => {
+                report.files.push(checked.stats);
+                report.violations.extend(checked.violations);
+                report.suppressions.extend(checked.suppressions);
+            },
+            Err(error) => report.parse_errors.push(error),
         }
     }
 
-    assert!(errors.is_empty(), "Unchecked arithmetic expressions found in the codebase.");
+    return report;
 }
 
 #[macro_export]
 macro_rules! assert_checked_subtree {
-    () => {
+    (@root $check:expr) => {
         {
             let mut get_root_cargo_toml_command = std::process::Command::new("cargo");
             get_root_cargo_toml_command.arg("locate-project").args(["--message-format", "plain"]).arg("--workspace");
@@ -172,14 +139,20 @@ macro_rules! assert_checked_subtree {
             let current_mod_root = workspace_root.join(current_mod_relative_path);
             let current_mod_root = current_mod_root.parent().unwrap();
 
-            alloy_checked_math::assert_checked(&current_mod_root);
+            ($check)(current_mod_root.as_ref());
         }
     };
+    () => {
+        $crate::assert_checked_subtree!(@root |root: &std::path::Path| alloy_checked_math::assert_checked(root))
+    };
+    ($config:expr) => {
+        $crate::assert_checked_subtree!(@root |root: &std::path::Path| alloy_checked_math::assert_checked_with_config(root, $config))
+    };
 }
 
 #[macro_export]
 macro_rules! assert_checked_mod {
-    () => {
+    (@root $check:expr) => {
         {
             let mut get_root_cargo_toml_command = std::process::Command::new("cargo");
             get_root_cargo_toml_command.arg("locate-project").args(["--message-format", "plain"]).arg("--workspace");
@@ -190,7 +163,272 @@ macro_rules! assert_checked_mod {
             let current_mod_relative_path = std::path::PathBuf::from(file!());
             let current_mod_root = workspace_root.join(current_mod_relative_path);
 
-            alloy_checked_math::assert_checked(&current_mod_root);
+            ($check)(current_mod_root.as_ref());
         }
     };
+    () => {
+        $crate::assert_checked_mod!(@root |root: &std::path::Path| alloy_checked_math::assert_checked(root))
+    };
+    ($config:expr) => {
+        $crate::assert_checked_mod!(@root |root: &std::path::Path| alloy_checked_math::assert_checked_with_config(root, $config))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_errors(source: &str, config: Config) -> usize {
+        check_file("".as_ref(), "test.rs".as_ref(), source, &config).unwrap().violations.len()
+    }
+
+    #[test]
+    fn test_float_arithmetic() {
+        let source = r#"
+            fn f(a: u32, b: u32, x: f64) -> f64 {
+                let mean = (a + b) as f64 / 2.0;
+                let scaled = -x * f64::EPSILON;
+                let ratio: f32 = a as f32 / b as f32;
+                let weight = |w: f64| w * x;
+                mean + scaled + ratio as f64 + x + weight(-x)
+            }
+
+            fn g(x: f64, n: u64) -> u64 {
+                let x = x as u64;
+                x * n + match n { 0 => -x, x => x - 1 }
+            }
+        "#;
+
+        assert_eq!(count_errors(source, Config::default()), 6);
+
+        // Only integer arithmetic is left: `a + b` and `g`, where `x` is shadowed by an integer.
+        assert_eq!(count_errors(source, Config::default().with_float_arithmetic(FloatArithmetic::Ignore)), 2);
+    }
+
+    #[test]
+    fn test_float_functions() {
+        let source = r#"
+            fn f(x: f64, y: f32) -> f64 {
+                let root = x.sqrt().max(1.0) * 2.0;
+                let power = f64::powi(x, 2) - root;
+                (y.abs() + 1.0) as f64 + power / f64::from(y)
+            }
+
+            fn g(x: f64, y: f32) -> u64 {
+                let bits = x.to_bits() + 1;
+                let exponent = f64::MANTISSA_DIGITS + y.to_bits() % 8;
+                bits + f64::to_bits(x) + 1 + exponent as u64 + x.is_nan() as u64 + 1
+            }
+        "#;
+
+        assert_eq!(count_errors(source, Config::default()), 6);
+
+        // Bit patterns, flags and digit counts of floats are integers, so every statement of `g` is left.
+        assert_eq!(count_errors(source, Config::default().with_float_arithmetic(FloatArithmetic::Ignore)), 3);
+    }
+
+    #[test]
+    fn test_const_closures() {
+        let source = r#"
+            const DOUBLE: fn(u64) -> u64 = |x| x * 2;
+            static FEES: [fn(u64) -> u64; 2] = [|x| x / 100, |x| x * 3 / 1_000];
+
+            trait Scale {
+                const SCALE: fn(u64) -> u64 = |x| x * 10;
+            }
+
+            #[checked_impl]
+            impl Vault {
+                const HALF: fn(u64) -> Option<u64> = |x| -> Option<u64> { Some(x / 2) };
+            }
+
+            fn f() -> u64 {
+                let shift = const { |x: u64| x + 1 };
+                shift(1 + 1)
+            }
+        "#;
+
+        // `1 + 1` is at runtime too, everything else only in the closures.
+        assert_eq!(count_errors(source, Config::default()), 6);
+    }
+
+    #[test]
+    fn test_checked_scopes() {
+        let source = r#"
+            #[checked_impl]
+            impl Pool {
+                fn f(a: u32) -> Result<u32, Error> { Ok(a + 1) }
+            }
+
+            #[alloy_checked_math::checked_impl]
+            trait Fees {
+                fn fee(&self, a: u32) -> Result<u32, Error> { Ok(a / 100) }
+            }
+
+            trait Unchecked {
+                fn fee(&self, a: u32) -> u32 { a / 100 }
+            }
+
+            #[checked_mod]
+            mod math {
+                fn g(a: u32) -> Result<u32, Error> { Ok(a * 2) }
+            }
+
+            mod other {
+                fn h(a: u32) -> u32 { a * 2 }
+            }
+        "#;
+
+        assert_eq!(count_errors(source, Config::default()), 2);
+    }
+
+    #[test]
+    fn test_const_contexts() {
+        let source = r#"
+            const BPS: u64 = 10_000;
+            static MAX_FEE: u64 = BPS * 3 / 10;
+
+            struct Ticks;
+
+            impl Ticks {
+                const MIN: i32 = -887_272;
+
+                fn buffer(a: u64) -> [u64; 2 + 2] {
+                    let padded = [a; 1 + 1];
+                    let _ = const { BPS - 1 };
+                    let _ = split::<{ 4 * 2 }>(padded);
+                    [0; 2 + 2]
+                }
+            }
+
+            const fn fee(amount: u64) -> u64 {
+                amount * 30 / BPS
+            }
+        "#;
+
+        assert_eq!(count_errors(source, Config::default()), 1);
+    }
+
+    #[test]
+    fn test_config_rules() {
+        let source = r#"
+            fn f(a: u32, b: u32) -> u32 { a * b + a / b - a % b }
+
+            #[allow_overflow]
+            fn g(a: u32) -> u32 { a + 1 }
+
+            #[cfg(test)]
+            mod tests {
+                #[test]
+                fn h() { assert_eq!(1 + 1, 2) }
+            }
+        "#;
+
+        assert_eq!(count_errors(source, Config::default()), 3);
+        assert_eq!(count_errors(source, Config::default().with_exempt_attribute("allow_overflow").with_tests(TestCode::Skip)), 1);
+
+        // Operands of an operator that isn't checked are still checked.
+        assert_eq!(count_errors(source, Config::default().with_operators([Operator::Div, Operator::Rem])), 2);
+        assert_eq!(count_errors(source, Config::default().with_severity(Operator::Add, Severity::Allow)), 1);
+
+        let violations = check_file("".as_ref(), "test.rs".as_ref(), source, &Config::default().with_severity(Operator::Sub, Severity::Warning)).unwrap().violations;
+        let severities: Vec<_> = violations.iter().map(|violation| violation.severity).collect();
+        assert_eq!(severities, [Severity::Warning, Severity::Error, Severity::Error]);
+    }
+
+    #[test]
+    fn test_macro_arguments() {
+        let source = r#"
+            macro_rules! double {
+                ($a:expr) => { $a * 2 };
+            }
+
+            fn f(a: u32, b: u32) -> Vec<u32> {
+                assert!(a + b > 0, "{}", a - b);
+                let _ = matches!(a * b, 0..10);
+                let _ = checked!(a * b);
+                let _ = unchecked! { a * b };
+                let _ = log!(target: "fees", a / b);
+                vec![a; b as usize + 1]
+            }
+        "#;
+
+        assert_eq!(count_errors(source, Config::default()), 4);
+        assert_eq!(count_errors(source, Config::default().with_exempt_macro("assert")), 2);
+    }
+
+    #[test]
+    fn test_suppressions() {
+        let source = r#"
+            #[allow_unchecked_math(reason = "bounded by the pool size")]
+            fn f(a: u32, b: u32) -> u32 { a * b }
+
+            fn g(a: u32, b: u32) -> u32 {
+                // checked-math: allow(b is at most 100)
+                let fee = a / b;
+                let total = a + fee; // checked-math: allow()
+                #[allow_unchecked_math]
+                let rest = a - total;
+                rest % b
+            }
+        "#;
+
+        let FileCheck { stats, violations, suppressions, .. } = check_file("".as_ref(), "test.rs".as_ref(), source, &Config::default()).unwrap();
+        assert_eq!((stats.violations, violations.len()), (1, 1));
+        assert_eq!(violations[0].expression, "rest % b");
+
+        let summary: Vec<_> = suppressions.iter().map(|suppression| {
+            (suppression.kind, suppression.function.as_deref(), suppression.reason.as_str(), suppression.violations.len())
+        }).collect();
+
+        assert_eq!(summary, [
+            (SuppressionKind::Comment, Some("test::g"), "b is at most 100", 1),
+            (SuppressionKind::Comment, Some("test::g"), "", 1),
+            (SuppressionKind::Attribute, Some("test::f"), "bounded by the pool size", 1),
+            (SuppressionKind::Attribute, Some("test::g"), "", 1),
+        ]);
+
+        assert_eq!(suppressions[0].span.start, Location { line: 6, column: 17 });
+        assert_eq!(suppressions[2].span.start, Location { line: 2, column: 13 });
+        assert!(!suppressions[1].is_justified());
+    }
+
+    #[test]
+    fn test_report() {
+        let source = r#"
+            #[checked_fn]
+            fn checked(a: u32) -> Result<u32, Error> { Ok(a + 1) }
+
+            fn fee(amount: u64, bps: u64) -> u64 {
+                let mut fee = amount * bps;
+                fee /= 10_000;
+                -(fee as i64) as u64
+            }
+        "#;
+
+        let FileCheck { stats, violations, .. } = check_file("".as_ref(), "fees.rs".as_ref(), source, &Config::default()).unwrap();
+        assert_eq!((stats.functions, stats.checked_scopes, stats.violations), (1, 1, 3));
+
+        let kinds: Vec<_> = violations.iter().map(|violation| violation.kind).collect();
+        assert_eq!(kinds, [ViolationKind::Mul, ViolationKind::DivAssign, ViolationKind::Neg]);
+        assert_eq!(violations[0].function.as_deref(), Some("fees::fee"));
+        assert_eq!(violations[0].expression, "amount * bps");
+        assert_eq!(violations[1].file, std::path::Path::new("fees.rs"));
+
+        let span = violations[1].span;
+        assert_eq!((span.start, span.end), (Location { line: 7, column: 21 }, Location { line: 7, column: 23 }));
+        assert_eq!(violations[1].source_line, "                fee /= 10_000;");
+
+        let error = check_file("".as_ref(), "broken.rs".as_ref(), "fn broken() -> {}", &Config::default()).unwrap_err();
+        assert_eq!(error.file, std::path::Path::new("broken.rs"));
+        assert_eq!(error.location, Some(Location { line: 1, column: 16 }));
+
+        assert_eq!(module_path("pool/math/mod.rs".as_ref()), ["pool", "math"]);
+        assert_eq!(module_path("pool/math.rs".as_ref()), ["pool", "math"]);
+        assert_eq!(module_path("lib.rs".as_ref()), Vec::<String>::new());
+
+        let report = check_path("missing", &Config::default());
+        assert!(!report.is_clean());
+        assert_eq!(report.parse_errors.len(), 1);
+    }
 }
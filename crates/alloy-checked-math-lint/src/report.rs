@@ -0,0 +1,340 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Operator, Severity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViolationKind {
+    Add,
+    AddAssign,
+    Sub,
+    SubAssign,
+    Mul,
+    MulAssign,
+    Div,
+    DivAssign,
+    Rem,
+    RemAssign,
+    Neg,
+}
+
+impl ViolationKind {
+    pub fn from_binary_op(op: syn::BinOp) -> Option<Self> {
+        return Some(match op {
+            syn::BinOp::Add(_) => ViolationKind::Add,
+            syn::BinOp::AddAssign(_) => ViolationKind::AddAssign,
+            syn::BinOp::Sub(_) => ViolationKind::Sub,
+            syn::BinOp::SubAssign(_) => ViolationKind::SubAssign,
+            syn::BinOp::Mul(_) => ViolationKind::Mul,
+            syn::BinOp::MulAssign(_) => ViolationKind::MulAssign,
+            syn::BinOp::Div(_) => ViolationKind::Div,
+            syn::BinOp::DivAssign(_) => ViolationKind::DivAssign,
+            syn::BinOp::Rem(_) => ViolationKind::Rem,
+            syn::BinOp::RemAssign(_) => ViolationKind::RemAssign,
+            _ => return None,
+        });
+    }
+
+    pub fn from_unary_op(op: syn::UnOp) -> Option<Self> {
+        return match op {
+            syn::UnOp::Neg(_) => Some(ViolationKind::Neg),
+            _ => None,
+        };
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ViolationKind::Add => "add",
+            ViolationKind::AddAssign => "add_assign",
+            ViolationKind::Sub => "sub",
+            ViolationKind::SubAssign => "sub_assign",
+            ViolationKind::Mul => "mul",
+            ViolationKind::MulAssign => "mul_assign",
+            ViolationKind::Div => "div",
+            ViolationKind::DivAssign => "div_assign",
+            ViolationKind::Rem => "rem",
+            ViolationKind::RemAssign => "rem_assign",
+            ViolationKind::Neg => "neg",
+        }
+    }
+
+    /// The operator a configuration refers to, compound assignments count as their operator.
+    pub fn rule(&self) -> Operator {
+        match self {
+            ViolationKind::Add | ViolationKind::AddAssign => Operator::Add,
+            ViolationKind::Sub | ViolationKind::SubAssign => Operator::Sub,
+            ViolationKind::Mul | ViolationKind::MulAssign => Operator::Mul,
+            ViolationKind::Div | ViolationKind::DivAssign => Operator::Div,
+            ViolationKind::Rem | ViolationKind::RemAssign => Operator::Rem,
+            ViolationKind::Neg => Operator::Neg,
+        }
+    }
+
+    pub fn operator(&self) -> &'static str {
+        match self {
+            ViolationKind::Add => "+",
+            ViolationKind::AddAssign => "+=",
+            ViolationKind::Sub => "-",
+            ViolationKind::SubAssign => "-=",
+            ViolationKind::Mul => "*",
+            ViolationKind::MulAssign => "*=",
+            ViolationKind::Div => "/",
+            ViolationKind::DivAssign => "/=",
+            ViolationKind::Rem => "%",
+            ViolationKind::RemAssign => "%=",
+            ViolationKind::Neg => "-",
+        }
+    }
+}
+
+/// A position in a source file, with 1-based lines and 1-based character columns like rustc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<proc_macro2::LineColumn> for Location {
+    fn from(location: proc_macro2::LineColumn) -> Self {
+        Location { line: location.line, column: location.column + 1 }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A source range, `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SourceSpan {
+    pub start: Location,
+    pub end: Location,
+}
+
+impl From<proc_macro2::Span> for SourceSpan {
+    fn from(span: proc_macro2::Span) -> Self {
+        SourceSpan { start: span.start().into(), end: span.end().into() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub file: PathBuf,
+    /// The span of the operator.
+    pub span: SourceSpan,
+    /// The qualified path of the enclosing function, such as `pool::Pool::swap`, `None` for
+    /// arithmetic outside of function bodies.
+    pub function: Option<String>,
+    pub expression: String,
+    pub expression_span: SourceSpan,
+    pub kind: ViolationKind,
+    /// `Error` or `Warning`, allowed operators are not reported.
+    pub severity: Severity,
+    /// The source line of the operator.
+    pub source_line: String,
+}
+
+impl Violation {
+    pub fn message(&self) -> String {
+        match &self.function {
+            Some(function) => format!("unchecked arithmetic `{}` in `{function}`", self.expression),
+            None => format!("unchecked arithmetic `{}`", self.expression),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionKind {
+    /// `#[allow_unchecked_math(reason = "...")]` on an item or statement.
+    Attribute,
+    /// `// checked-math: allow(...)` on the line of a statement or item, or on its own line above it.
+    Comment,
+}
+
+impl SuppressionKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SuppressionKind::Attribute => "attribute",
+            SuppressionKind::Comment => "comment",
+        }
+    }
+}
+
+/// Unchecked arithmetic allowed on purpose, with the findings it allows.
+#[derive(Debug, Clone)]
+pub struct Suppression {
+    pub file: PathBuf,
+    /// The span of the attribute or comment.
+    pub span: SourceSpan,
+    pub function: Option<String>,
+    pub kind: SuppressionKind,
+    /// The justification, a suppression without one fails the check.
+    pub reason: String,
+    pub violations: Vec<Violation>,
+    /// The source line of the attribute or comment.
+    pub source_line: String,
+}
+
+impl Suppression {
+    pub fn is_justified(&self) -> bool {
+        !self.reason.trim().is_empty()
+    }
+
+    pub fn message(&self) -> String {
+        let function = self.function.as_ref().map(|function| format!(" in `{function}`")).unwrap_or_default();
+
+        match self.is_justified() {
+            true => format!("allowed {} unchecked arithmetic expressions{function}: {}", self.violations.len(), self.reason),
+            false => format!("unchecked arithmetic allowed without a reason{function}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub file: PathBuf,
+    /// Where parsing failed, `None` if the file could not be read.
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FileStats {
+    pub file: PathBuf,
+    /// Function bodies that were linted.
+    pub functions: usize,
+    /// Functions, impls, traits and modules skipped because they are checked or explicitly unchecked.
+    pub checked_scopes: usize,
+    pub violations: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// The checked directory, or the parent directory when a single file was checked.
+    pub root: PathBuf,
+    pub files: Vec<FileStats>,
+    pub violations: Vec<Violation>,
+    pub suppressions: Vec<Suppression>,
+    pub parse_errors: Vec<ParseError>,
+}
+
+impl Report {
+    /// Whether the check passes: warnings are reported but don't fail it, suppressions without a
+    /// reason do.
+    pub fn is_clean(&self) -> bool {
+        self.errors().next().is_none() && self.unjustified().next().is_none() && self.parse_errors.is_empty()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Violation> {
+        self.violations.iter().filter(|violation| violation.severity == Severity::Error)
+    }
+
+    pub fn unjustified(&self) -> impl Iterator<Item = &Suppression> {
+        self.suppressions.iter().filter(|suppression| !suppression.is_justified())
+    }
+
+    /// Appends the results of another check, keeping this report's root.
+    pub fn merge(&mut self, other: Report) {
+        self.files.extend(other.files);
+        self.violations.extend(other.violations);
+        self.suppressions.extend(other.suppressions);
+        self.parse_errors.extend(other.parse_errors);
+    }
+
+    pub fn relative_path<'a>(&self, file: &'a Path) -> &'a Path {
+        file.strip_prefix(&self.root).unwrap_or(file)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for stats in &self.files {
+            write!(f, "Checking {} ... ", self.relative_path(&stats.file).display())?;
+
+            if stats.violations > 0 {
+                writeln!(f, "found {} unchecked arithmetic expressions", stats.violations)?;
+            } else {
+                writeln!(f, "done")?;
+            }
+        }
+
+        for error in &self.parse_errors {
+            let location = error.location.map(|location| format!(":{location}")).unwrap_or_default();
+            writeln!(f, "Failed to check {}{location}: {}", self.relative_path(&error.file).display(), error.message)?;
+        }
+
+        for suppression in self.suppressions.iter().filter(|suppression| suppression.is_justified()) {
+            let path = self.relative_path(&suppression.file).display();
+            writeln!(f, "Allowed {} unchecked arithmetic expressions at {path}:{}: {}", suppression.violations.len(), suppression.span.start, suppression.reason)?;
+        }
+
+        if self.violations.is_empty() {
+            writeln!(f, "No unchecked arithmetic expressions found in the codebase.")?;
+        } else {
+            write!(f, "Found total {} unchecked arithmetic expressions", self.violations.len())?;
+
+            match self.violations.len() - self.errors().count() {
+                0 => writeln!(f)?,
+                warnings => writeln!(f, ", {warnings} of them warnings")?,
+            }
+        }
+
+        for violation in &self.violations {
+            let label = format!("unchecked `{}`", violation.kind.operator());
+            writeln!(f)?;
+            write_snippet(f, violation.severity.name(), &violation.message(), self.relative_path(&violation.file), violation.span, &violation.source_line, &label)?;
+        }
+
+        for suppression in self.unjustified() {
+            let label = format!("add a reason to this {}", suppression.kind.name());
+            writeln!(f)?;
+            write_snippet(f, "error", &suppression.message(), self.relative_path(&suppression.file), suppression.span, &suppression.source_line, &label)?;
+        }
+
+        return Ok(());
+    }
+}
+
+// error: unchecked arithmetic `amount * bps` in `pool::Pool::fee`
+//  --> pool.rs:7:16
+//   |
+// 7 |         amount * bps
+//   |                ^ unchecked `*`
+fn write_snippet(f: &mut fmt::Formatter<'_>, level: &str, message: &str, path: &Path, span: SourceSpan, source_line: &str, label: &str) -> fmt::Result {
+    let SourceSpan { start, end } = span;
+    let gutter = " ".repeat(start.line.to_string().len());
+
+    // Reuse the tabs of the source line so the caret lines up in any tab width.
+    let indent: String = source_line.chars().take(start.column - 1).map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+    let carets = "^".repeat(if end.line == start.line { end.column.saturating_sub(start.column).max(1) } else { 1 });
+
+    writeln!(f, "{level}: {message}")?;
+    writeln!(f, "{gutter}--> {}:{start}", path.display())?;
+    writeln!(f, "{gutter} |")?;
+    writeln!(f, "{} | {}", start.line, source_line)?;
+    writeln!(f, "{gutter} | {indent}{carets} {label}")?;
+
+    return Ok(());
+}
+
+pub(crate) fn pretty_expr(expr: &syn::Expr) -> String {
+    let file = syn::File {
+        shebang: None,
+        attrs: vec![],
+        items: vec![syn::parse_quote! {
+            fn __checked_expr() {#expr}
+        }],
+    };
+
+    let unparsed = prettyplease::unparse(&file);
+
+    return unparsed
+        .trim()
+        .strip_prefix("fn __checked_expr() {").unwrap()
+        .strip_suffix("}").unwrap()
+        .trim()
+        .trim_end_matches(';')
+        .to_string();
+}
@@ -0,0 +1,696 @@
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+
+use crate::audit::{EscapeHatch, EscapeHatchKind};
+use crate::config::{Config, FloatArithmetic, Operator, Severity, TestCode};
+use crate::report::{FileStats, Location, SourceSpan, Suppression, SuppressionKind, Violation, ViolationKind};
+
+// Macros whose arguments are checked or deliberately unchecked by the macro crate.
+const CHECKED_MACROS: &[&str] = &["checked", "checked_or_panic", "const_checked", "try_checked", "checked_closure", "unchecked"];
+
+fn is_checked_binary_op(op: syn::BinOp) -> bool {
+    ViolationKind::from_binary_op(op).is_some()
+}
+
+// Methods and associated functions of `f32` and `f64` that return a float, unlike `to_bits` or
+// `is_nan`.
+const FLOAT_FUNCTIONS: &[&str] = &[
+    "abs", "acos", "acosh", "asin", "asinh", "atan", "atan2", "atanh", "cbrt", "ceil", "clamp", "copysign", "cos", "cosh",
+    "div_euclid", "exp", "exp2", "exp_m1", "floor", "fract", "from", "from_bits", "hypot", "ln", "ln_1p", "log", "log10",
+    "log2", "max", "maximum", "min", "minimum", "mul_add", "powf", "powi", "recip", "rem_euclid", "round",
+    "round_ties_even", "signum", "sin", "sinh", "sqrt", "tan", "tanh", "to_degrees", "to_radians", "trunc",
+];
+
+// Associated constants of `f32` and `f64` that are integers.
+const FLOAT_INTEGER_CONSTANTS: &[&str] = &["DIGITS", "MANTISSA_DIGITS", "MAX_10_EXP", "MAX_EXP", "MIN_10_EXP", "MIN_EXP", "RADIX"];
+
+fn is_float_path(path: &syn::Path) -> bool {
+    path.segments.first().is_some_and(|segment| segment.ident == "f32" || segment.ident == "f64")
+}
+
+fn is_float_function(ident: &syn::Ident) -> bool {
+    FLOAT_FUNCTIONS.iter().any(|name| ident == name)
+}
+
+fn is_float_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(ty) => ty.qself.is_none() && (ty.path.is_ident("f32") || ty.path.is_ident("f64")),
+        syn::Type::Paren(ty) => is_float_type(&ty.elem),
+        syn::Type::Group(ty) => is_float_type(&ty.elem),
+        _ => false,
+    }
+}
+
+// The variables a pattern binds.
+fn pattern_bindings(pat: &syn::Pat) -> Vec<String> {
+    struct Bindings(Vec<String>);
+
+    impl<'ast> Visit<'ast> for Bindings {
+        fn visit_pat_ident(&mut self, i: &'ast syn::PatIdent) {
+            self.0.push(i.ident.to_string());
+            syn::visit::visit_pat_ident(self, i);
+        }
+    }
+
+    let mut bindings = Bindings(Vec::new());
+    bindings.visit_pat(pat);
+    return bindings.0;
+}
+
+pub(crate) struct CheckedVisitor<'a> {
+    pub config: &'a Config,
+    /// The operators checked in this file.
+    pub operators: &'a [Operator],
+    pub source: &'a str,
+    pub current_file: std::path::PathBuf,
+    /// Modules, types, traits and functions enclosing the visited item, starting with the module of
+    /// the file.
+    pub scope: Vec<String>,
+    pub current_fn: Option<String>,
+    pub stats: FileStats,
+    pub violations: Vec<Violation>,
+    /// Suppression comments come first, in the order of `comment_lines`.
+    pub suppressions: Vec<Suppression>,
+    /// The line each suppression comment applies to.
+    comment_lines: Vec<usize>,
+    /// The suppression the visited code is in.
+    suppressing: Option<usize>,
+    pub escape_hatches: Vec<EscapeHatch>,
+    /// Whether the visited code is exempt, and only searched for escape hatches.
+    exempt: bool,
+    /// The variables in scope and whether they are `f32` or `f64`, innermost last.
+    bindings: Vec<(String, bool)>,
+}
+
+impl<'a> CheckedVisitor<'a> {
+    pub fn new(config: &'a Config, operators: &'a [Operator], file: std::path::PathBuf, source: &'a str, module: Vec<String>) -> Self {
+        let comments = suppression_comments(source);
+
+        CheckedVisitor {
+            config,
+            operators,
+            source,
+            stats: FileStats { file: file.clone(), ..FileStats::default() },
+            suppressions: comments.iter().map(|(span, reason, _)| Suppression {
+                file: file.clone(),
+                span: *span,
+                function: None,
+                kind: SuppressionKind::Comment,
+                reason: reason.clone(),
+                violations: Vec::new(),
+                source_line: source.lines().nth(span.start.line - 1).unwrap_or_default().to_string(),
+            }).collect(),
+            comment_lines: comments.into_iter().map(|(_, _, line)| line).collect(),
+            suppressing: None,
+            escape_hatches: Vec::new(),
+            exempt: false,
+            bindings: Vec::new(),
+            current_file: file,
+            scope: module,
+            current_fn: None,
+            violations: Vec::new(),
+        }
+    }
+
+    fn is_ignored(&self, expr: &syn::Expr) -> bool {
+        self.config.float_arithmetic == FloatArithmetic::Ignore && self.is_float_expr(expr)
+    }
+
+    // Whether an expression is a float as far as the syntax and the types of the variables in scope
+    // tell, without type inference.
+    fn is_float_expr(&self, expr: &syn::Expr) -> bool {
+        match expr {
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Float(_), .. }) => true,
+            syn::Expr::Cast(cast) => is_float_type(&cast.ty),
+            syn::Expr::Path(path) if path.qself.is_none() && path.path.segments.len() == 1 => {
+                self.bindings.iter().rev().find(|(name, _)| path.path.is_ident(name)).is_some_and(|&(_, float)| float)
+            },
+            syn::Expr::Path(path) => is_float_path(&path.path) && !FLOAT_INTEGER_CONSTANTS.iter().any(|name| path.path.segments.last().unwrap().ident == name),
+            syn::Expr::Call(call) => matches!(&*call.func, syn::Expr::Path(path) if is_float_path(&path.path) && is_float_function(&path.path.segments.last().unwrap().ident)),
+            syn::Expr::MethodCall(call) => is_float_function(&call.method) && self.is_float_expr(&call.receiver),
+            syn::Expr::Paren(paren) => self.is_float_expr(&paren.expr),
+            syn::Expr::Group(group) => self.is_float_expr(&group.expr),
+            syn::Expr::Unary(unary) => self.is_float_expr(&unary.expr),
+            syn::Expr::Binary(binary) => is_checked_binary_op(binary.op) && (self.is_float_expr(&binary.left) || self.is_float_expr(&binary.right)),
+            _ => false,
+        }
+    }
+
+    // Brings the variables of a pattern into scope, as floats if the pattern is a single variable
+    // of type `f32` or `f64`, or is bound to a float expression.
+    fn bind(&mut self, pat: &syn::Pat, init: Option<&syn::Expr>) {
+        let float = match pat {
+            syn::Pat::Type(pat) => matches!(&*pat.pat, syn::Pat::Ident(_)) && is_float_type(&pat.ty),
+            syn::Pat::Ident(ident) => ident.subpat.is_none() && init.is_some_and(|init| self.is_float_expr(init)),
+            _ => false,
+        };
+
+        self.bindings.extend(pattern_bindings(pat).into_iter().map(|name| (name, float)));
+    }
+
+    // Visits code with its own variables, which go out of scope after it.
+    fn visit_bindings<F: FnOnce(&mut Self)>(&mut self, visit: F) {
+        let outer = self.bindings.len();
+        visit(self);
+        self.bindings.truncate(outer);
+    }
+
+    // The severity of an operator in this file, `None` if it is not checked at all.
+    fn severity(&self, kind: ViolationKind) -> Option<Severity> {
+        let operator = kind.rule();
+        let severity = self.config.severity(operator);
+        (self.operators.contains(&operator) && severity != Severity::Allow).then_some(severity)
+    }
+
+    fn push_violation(&mut self, expr: syn::Expr, operator: proc_macro2::Span, kind: ViolationKind, severity: Severity) {
+        if self.exempt {
+            return;
+        }
+
+        let span = SourceSpan::from(operator);
+
+        let violation = Violation {
+            file: self.current_file.clone(),
+            span,
+            function: self.current_fn.clone(),
+            expression: crate::report::pretty_expr(&expr),
+            expression_span: expr.span().into(),
+            kind,
+            severity,
+            source_line: self.source.lines().nth(span.start.line - 1).unwrap_or_default().to_string(),
+        };
+
+        let comment = || self.comment_lines.iter().position(|&line| line == span.start.line);
+
+        match self.suppressing.or_else(comment) {
+            Some(index) => {
+                let suppression = &mut self.suppressions[index];
+                suppression.function = suppression.function.take().or_else(|| violation.function.clone());
+                suppression.violations.push(violation);
+            },
+            None => {
+                self.stats.violations += 1;
+                self.violations.push(violation);
+            },
+        }
+    }
+
+    // Visits a function or scope, quietly when it is exempt: it isn't linted then, but still
+    // searched for escape hatches. Skipped test code isn't searched at all.
+    fn visit_item_body<F: FnOnce(&mut Self)>(&mut self, attrs: &[syn::Attribute], span: proc_macro2::Span, ident: Option<&syn::Ident>, exempt: bool, visit: F) {
+        if exempt {
+            self.stats.checked_scopes += 1;
+
+            if self.config.tests == TestCode::Skip && is_test_code(attrs) {
+                return;
+            }
+        }
+
+        self.visit_suppressed(attrs, span, ident, |visitor| match exempt {
+            true => visitor.visit_quietly(visit),
+            false => visit(visitor),
+        });
+    }
+
+    // Only searches for escape hatches, in exempt code or the operands of a reported expression.
+    fn visit_quietly<F: FnOnce(&mut Self)>(&mut self, visit: F) {
+        let outer = std::mem::replace(&mut self.exempt, true);
+        visit(self);
+        self.exempt = outer;
+    }
+
+    fn visit_fn(&mut self, attrs: &[syn::Attribute], span: proc_macro2::Span, sig: &syn::Signature, block: Option<&syn::Block>) {
+        let ident = &sig.ident;
+        let exempt = self.is_exempt(attrs, has_checked_fn_attr);
+
+        self.visit_item_body(attrs, span, Some(ident), exempt, |visitor| {
+            if let Some(attr) = attrs.iter().find(|attr| is_attr(attr, "unchecked_fn")) {
+                let arithmetic = block.map(|block| arithmetic(|collector| collector.visit_block(block))).unwrap_or_default();
+                visitor.push_escape_hatch(EscapeHatchKind::UncheckedFn, attr.span(), Some(visitor.qualified(ident)), format!("fn {ident}"), arithmetic);
+            }
+
+            if let Some(block) = block {
+                visitor.visit_fn_block(sig, block);
+            }
+        });
+    }
+
+    fn visit_scope<F: FnOnce(&mut Self)>(&mut self, attrs: &[syn::Attribute], span: proc_macro2::Span, name: String, visit: F) {
+        let exempt = self.is_exempt(attrs, has_checked_scope_attr);
+        self.visit_item_body(attrs, span, None, exempt, |visitor| visitor.visit_scoped(name, visit));
+    }
+
+    // The path of a function declared in the current scope.
+    fn qualified(&self, ident: &syn::Ident) -> String {
+        self.scope.iter().map(String::as_str).chain([ident.to_string().as_str()]).collect::<Vec<_>>().join("::")
+    }
+
+    fn push_escape_hatch(&mut self, kind: EscapeHatchKind, span: proc_macro2::Span, function: Option<String>, code: String, arithmetic: Vec<String>) {
+        let span = SourceSpan::from(span);
+
+        self.escape_hatches.push(EscapeHatch {
+            file: self.current_file.clone(),
+            span,
+            function,
+            kind,
+            code: code.split_whitespace().collect::<Vec<_>>().join(" "),
+            arithmetic,
+            justification: self.justification(span.start.line),
+        });
+    }
+
+    // The reason of the suppression an escape hatch is in, or the comments directly above its line.
+    fn justification(&self, line: usize) -> Option<String> {
+        let comment = || self.comment_lines.iter().position(|&comment_line| comment_line == line);
+
+        if let Some(index) = self.suppressing.or_else(comment) {
+            let suppression = &self.suppressions[index];
+            return suppression.is_justified().then(|| suppression.reason.clone());
+        }
+
+        let mut comments: Vec<&str> = Vec::new();
+
+        for above in self.source.lines().take(line - 1).collect::<Vec<_>>().into_iter().rev().map(str::trim) {
+            match above.strip_prefix("//") {
+                Some(comment) => comments.push(comment.trim_start_matches(['/', '!']).trim()),
+                None if above.starts_with("#[") => continue,
+                None => break,
+            }
+        }
+
+        let justification = comments.into_iter().rev().filter(|comment| !comment.is_empty()).collect::<Vec<_>>().join(" ");
+        return (!justification.is_empty()).then_some(justification);
+    }
+
+    // Visits an item or statement in the suppression of its `allow_unchecked_math` attribute or of
+    // a comment on its first line, if it has one. `ident` names a function item.
+    fn visit_suppressed<F: FnOnce(&mut Self)>(&mut self, attrs: &[syn::Attribute], span: proc_macro2::Span, ident: Option<&syn::Ident>, visit: F) {
+        let function = match ident {
+            Some(ident) => Some(self.qualified(ident)),
+            None => self.current_fn.clone(),
+        };
+
+        let attribute = attrs.iter().find(|attr| is_attr(attr, "allow_unchecked_math")).map(|attr| {
+            let span = SourceSpan::from(attr.span());
+
+            self.suppressions.push(Suppression {
+                file: self.current_file.clone(),
+                span,
+                function: function.clone(),
+                kind: SuppressionKind::Attribute,
+                reason: attribute_reason(attr),
+                violations: Vec::new(),
+                source_line: self.source.lines().nth(span.start.line - 1).unwrap_or_default().to_string(),
+            });
+
+            self.suppressions.len() - 1
+        });
+
+        let comment = || {
+            let index = self.comment_lines.iter().position(|&line| line == span.start().line)?;
+            self.suppressions[index].function = self.suppressions[index].function.take().or(function);
+            Some(index)
+        };
+
+        let Some(index) = attribute.or_else(comment) else {
+            return visit(self);
+        };
+
+        let outer = self.suppressing.replace(index);
+        visit(self);
+        self.suppressing = outer;
+    }
+
+    fn is_exempt(&self, attrs: &[syn::Attribute], checked: fn(&[syn::Attribute]) -> bool) -> bool {
+        false
+            || checked(attrs)
+            || attrs.iter().any(|attr| self.config.exempt_attributes.iter().any(|name| is_attr(attr, name)))
+            || self.config.tests == TestCode::Skip && is_test_code(attrs)
+    }
+
+    fn is_exempt_macro(&self, mac: &syn::Macro) -> bool {
+        let Some(name) = mac.path.segments.last().map(|segment| segment.ident.to_string()) else {
+            return true;
+        };
+
+        CHECKED_MACROS.contains(&name.as_str()) || self.config.exempt_macros.contains(&name)
+    }
+
+    fn visit_scoped<F: FnOnce(&mut Self)>(&mut self, name: String, visit: F) {
+        self.scope.push(name);
+        visit(self);
+        self.scope.pop();
+    }
+
+    fn visit_fn_block(&mut self, sig: &syn::Signature, block: &syn::Block) {
+        if !self.exempt {
+            self.stats.functions += 1;
+        }
+
+        self.visit_scoped(sig.ident.to_string(), |visitor| visitor.visit_bindings(|visitor| {
+            for input in &sig.inputs {
+                if let syn::FnArg::Typed(input) = input {
+                    visitor.bind(&syn::Pat::Type(input.clone()), None);
+                }
+            }
+
+            let outer = visitor.current_fn.replace(visitor.scope.join("::"));
+            syn::visit::visit_block(visitor, block);
+            visitor.current_fn = outer;
+        }));
+    }
+}
+
+// `Pool` for `impl Pool<T>` and `impl Trait for Pool<T>`, the full type for anything else.
+fn type_name(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default(),
+        syn::Type::Reference(ty) => type_name(&ty.elem),
+        syn::Type::Paren(ty) => type_name(&ty.elem),
+        syn::Type::Group(ty) => type_name(&ty.elem),
+        ty => quote::ToTokens::to_token_stream(ty).to_string().replace(' ', ""),
+    }
+}
+
+fn is_attr(attr: &syn::Attribute, name: &str) -> bool {
+    attr.path().segments.last().is_some_and(|segment| segment.ident == name)
+}
+
+// The outermost arithmetic expressions in the code `visit` walks, checked or not.
+fn arithmetic<F: FnOnce(&mut Arithmetic)>(visit: F) -> Vec<String> {
+    let mut collector = Arithmetic(Vec::new());
+    visit(&mut collector);
+    return collector.0;
+}
+
+struct Arithmetic(Vec<String>);
+
+impl<'ast> Visit<'ast> for Arithmetic {
+    fn visit_expr_binary(&mut self, i: &'ast syn::ExprBinary) {
+        match ViolationKind::from_binary_op(i.op) {
+            Some(_) => self.0.push(crate::report::pretty_expr(&syn::Expr::Binary(i.clone()))),
+            None => syn::visit::visit_expr_binary(self, i),
+        }
+    }
+
+    fn visit_expr_unary(&mut self, i: &'ast syn::ExprUnary) {
+        match ViolationKind::from_unary_op(i.op) {
+            Some(_) => self.0.push(crate::report::pretty_expr(&syn::Expr::Unary(i.clone()))),
+            None => syn::visit::visit_expr_unary(self, i),
+        }
+    }
+
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        macro_exprs(i).iter().for_each(|expr| self.visit_expr(expr));
+    }
+}
+
+fn attribute_reason(attr: &syn::Attribute) -> String {
+    let mut reason = String::new();
+
+    let _ = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("reason") {
+            reason = meta.value()?.parse::<syn::LitStr>()?.value();
+        }
+
+        Ok(())
+    });
+
+    return reason;
+}
+
+// `// checked-math: allow(reason)` comments, with the reason and the line they apply to: their own
+// line after code, the next line with code otherwise. Comment markers inside string literals on the
+// same line are skipped, anything else is matched by text as `syn` doesn't keep comments.
+fn suppression_comments(source: &str) -> Vec<(SourceSpan, String, usize)> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut comments = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let Some(start) = line.match_indices("//").map(|(start, _)| start).find(|&start| {
+            line[..start].matches('"').count() % 2 == 0 && line[start + 2..].trim_start().starts_with("checked-math:")
+        }) else {
+            continue;
+        };
+
+        let directive = line[start + 2..].trim_start()["checked-math:".len()..].trim();
+        let reason = directive.strip_prefix("allow").map(str::trim).and_then(|args| args.strip_prefix('(')?.strip_suffix(')'));
+
+        let applies_to = match line[..start].trim().is_empty() {
+            true => (index + 1..lines.len()).find(|&next| !lines[next].trim().is_empty() && !lines[next].trim_start().starts_with("//")),
+            false => Some(index),
+        };
+
+        let line_number = index + 1;
+        let span = SourceSpan {
+            start: Location { line: line_number, column: line[..start].chars().count() + 1 },
+            end: Location { line: line_number, column: line.chars().count() + 1 },
+        };
+
+        comments.push((span, reason.unwrap_or_default().trim().to_string(), applies_to.map_or(0, |next| next + 1)));
+    }
+
+    return comments;
+}
+
+fn is_test_code(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| false
+        || is_attr(attr, "test")
+        || attr.path().is_ident("cfg") && attr.parse_args::<syn::Ident>().is_ok_and(|ident| ident == "test")
+    )
+}
+
+// The expressions of a function-like macro whose arguments look like code: `assert!(a + b > c)`,
+// `vec![a; n + 1]` or `matches!(a - b, 0..10)`, nothing for anything else.
+pub(crate) fn macro_exprs(mac: &syn::Macro) -> Vec<syn::Expr> {
+    use syn::parse::Parser;
+    use syn::punctuated::Punctuated;
+
+    let list = |input: syn::parse::ParseStream| Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated(input);
+
+    if let Ok(exprs) = list.parse2(mac.tokens.clone()) {
+        return exprs.into_iter().collect();
+    }
+
+    let repeat = |input: syn::parse::ParseStream| {
+        let expr: syn::Expr = input.parse()?;
+        input.parse::<syn::Token![;]>()?;
+        Ok(vec![expr, input.parse()?])
+    };
+
+    let leading = |input: syn::parse::ParseStream| {
+        let expr: syn::Expr = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        input.parse::<proc_macro2::TokenStream>()?;
+        Ok(vec![expr])
+    };
+
+    return repeat.parse2(mac.tokens.clone()).or_else(|_| leading.parse2(mac.tokens.clone())).unwrap_or_default();
+}
+
+// Visits the closures in a const-evaluated expression, and the items declared in it.
+struct ConstClosures<'v, 'a>(&'v mut CheckedVisitor<'a>);
+
+impl<'ast> Visit<'ast> for ConstClosures<'_, '_> {
+    fn visit_expr_closure(&mut self, i: &'ast syn::ExprClosure) {
+        self.0.visit_expr_closure(i);
+    }
+
+    fn visit_item(&mut self, i: &'ast syn::Item) {
+        self.0.visit_item(i);
+    }
+}
+
+fn has_checked_fn_attr(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| false
+        || is_attr(attr, "checked_fn")
+        || is_attr(attr, "unchecked_fn")
+    )
+}
+
+fn has_checked_scope_attr(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| false
+        || is_attr(attr, "checked_impl")
+        || is_attr(attr, "checked_mod")
+    )
+}
+
+impl<'ast> Visit<'ast> for CheckedVisitor<'_> {
+    fn visit_item_fn(&mut self, i: &'ast syn::ItemFn) {
+        self.visit_fn(&i.attrs, i.span(), &i.sig, Some(&i.block));
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast syn::ImplItemFn) {
+        self.visit_fn(&i.attrs, i.span(), &i.sig, Some(&i.block));
+    }
+
+    fn visit_trait_item_fn(&mut self, i: &'ast syn::TraitItemFn) {
+        self.visit_fn(&i.attrs, i.span(), &i.sig, i.default.as_ref());
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast syn::ItemImpl) {
+        self.visit_scope(&i.attrs, i.span(), type_name(&i.self_ty), |visitor| syn::visit::visit_item_impl(visitor, i));
+    }
+
+    fn visit_item_trait(&mut self, i: &'ast syn::ItemTrait) {
+        self.visit_scope(&i.attrs, i.span(), i.ident.to_string(), |visitor| syn::visit::visit_item_trait(visitor, i));
+    }
+
+    fn visit_item_mod(&mut self, i: &'ast syn::ItemMod) {
+        self.visit_scope(&i.attrs, i.span(), i.ident.to_string(), |visitor| syn::visit::visit_item_mod(visitor, i));
+    }
+
+    // Items go through their own visitors, expression statements can only carry attributes on nightly.
+    fn visit_stmt(&mut self, i: &'ast syn::Stmt) {
+        let attrs = match i {
+            syn::Stmt::Item(_) => return syn::visit::visit_stmt(self, i),
+            syn::Stmt::Local(local) => &local.attrs[..],
+            syn::Stmt::Macro(mac) => &mac.attrs[..],
+            syn::Stmt::Expr(..) => &[],
+        };
+
+        self.visit_suppressed(attrs, i.span(), None, |visitor| syn::visit::visit_stmt(visitor, i));
+    }
+
+    fn visit_block(&mut self, i: &'ast syn::Block) {
+        self.visit_bindings(|visitor| syn::visit::visit_block(visitor, i));
+    }
+
+    // The initializer is visited before the variables it initializes are in scope.
+    fn visit_local(&mut self, i: &'ast syn::Local) {
+        syn::visit::visit_local(self, i);
+        self.bind(&i.pat, i.init.as_ref().map(|init| &*init.expr));
+    }
+
+    fn visit_expr_closure(&mut self, i: &'ast syn::ExprClosure) {
+        self.visit_bindings(|visitor| {
+            i.inputs.iter().for_each(|input| visitor.bind(input, None));
+            syn::visit::visit_expr_closure(visitor, i);
+        });
+    }
+
+    fn visit_arm(&mut self, i: &'ast syn::Arm) {
+        self.visit_bindings(|visitor| {
+            visitor.bind(&i.pat, None);
+            syn::visit::visit_arm(visitor, i);
+        });
+    }
+
+    fn visit_expr_for_loop(&mut self, i: &'ast syn::ExprForLoop) {
+        self.visit_bindings(|visitor| {
+            visitor.bind(&i.pat, None);
+            syn::visit::visit_expr_for_loop(visitor, i);
+        });
+    }
+
+    // `if let` and `while let` bindings shadow outer variables until the end of the enclosing block.
+    fn visit_expr_let(&mut self, i: &'ast syn::ExprLet) {
+        syn::visit::visit_expr_let(self, i);
+        self.bind(&i.pat, None);
+    }
+
+    // Constants, statics, array lengths, inline `const` blocks and const generic arguments are
+    // evaluated at compile time, where overflow is already an error. Only the closures they create
+    // run at runtime.
+    fn visit_item_const(&mut self, i: &'ast syn::ItemConst) {
+        ConstClosures(self).visit_expr(&i.expr);
+    }
+
+    fn visit_item_static(&mut self, i: &'ast syn::ItemStatic) {
+        ConstClosures(self).visit_expr(&i.expr);
+    }
+
+    fn visit_impl_item_const(&mut self, i: &'ast syn::ImplItemConst) {
+        ConstClosures(self).visit_expr(&i.expr);
+    }
+
+    fn visit_trait_item_const(&mut self, i: &'ast syn::TraitItemConst) {
+        if let Some((_, expr)) = &i.default {
+            ConstClosures(self).visit_expr(expr);
+        }
+    }
+
+    fn visit_expr_const(&mut self, i: &'ast syn::ExprConst) {
+        ConstClosures(self).visit_block(&i.block);
+    }
+
+    fn visit_type_array(&mut self, i: &'ast syn::TypeArray) {
+        self.visit_type(&i.elem);
+    }
+
+    fn visit_expr_repeat(&mut self, i: &'ast syn::ExprRepeat) {
+        self.visit_expr(&i.expr);
+    }
+
+    fn visit_generic_argument(&mut self, i: &'ast syn::GenericArgument) {
+        if let syn::GenericArgument::Const(_) = i {
+            return;
+        }
+
+        syn::visit::visit_generic_argument(self, i);
+    }
+
+    // Item-position macros like `macro_rules!` or `thread_local!` don't take expressions.
+    fn visit_item_macro(&mut self, _: &'ast syn::ItemMacro) {}
+
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        if i.path.segments.last().is_some_and(|segment| segment.ident == "unchecked") {
+            let stmts = syn::parse::Parser::parse2(syn::Block::parse_within, i.tokens.clone()).unwrap_or_default();
+            let arithmetic = arithmetic(|collector| stmts.iter().for_each(|stmt| collector.visit_stmt(stmt)));
+            let code = crate::report::pretty_expr(&syn::Expr::Macro(syn::ExprMacro { attrs: Vec::new(), mac: i.clone() }));
+            self.push_escape_hatch(EscapeHatchKind::UncheckedMacro, i.span(), self.current_fn.clone(), code, arithmetic);
+        }
+
+        if self.is_exempt_macro(i) {
+            return;
+        }
+
+        for expr in macro_exprs(i) {
+            self.visit_expr(&expr);
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast syn::ExprMethodCall) {
+        let method = i.method.to_string();
+
+        let kind = match () {
+            _ if method.starts_with("wrapping_") => Some(EscapeHatchKind::WrappingMethod),
+            _ if method.starts_with("saturating_") => Some(EscapeHatchKind::SaturatingMethod),
+            _ => None,
+        };
+
+        if let Some(kind) = kind {
+            let code = crate::report::pretty_expr(&syn::Expr::MethodCall(i.clone()));
+            self.push_escape_hatch(kind, i.span(), self.current_fn.clone(), code.clone(), vec![code]);
+        }
+
+        syn::visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if let Some((kind, severity)) = ViolationKind::from_binary_op(node.op).and_then(|kind| Some((kind, self.severity(kind)?))) {
+            let expr = syn::Expr::Binary(node.clone());
+
+            if !self.is_ignored(&expr) {
+                self.push_violation(expr, node.op.span(), kind, severity);
+                return self.visit_quietly(|visitor| syn::visit::visit_expr_binary(visitor, node));
+            }
+        }
+
+        self.visit_expr(&node.left);
+        self.visit_expr(&node.right);
+    }
+
+    fn visit_expr_unary(&mut self, node: &'ast syn::ExprUnary) {
+        if let Some((kind, severity)) = ViolationKind::from_unary_op(node.op).and_then(|kind| Some((kind, self.severity(kind)?))) {
+            let expr = syn::Expr::Unary(node.clone());
+
+            if !self.is_ignored(&expr) {
+                self.push_violation(expr, node.op.span(), kind, severity);
+                return self.visit_quietly(|visitor| syn::visit::visit_expr_unary(visitor, node));
+            }
+        }
+
+        self.visit_expr(&node.expr);
+    }
+}
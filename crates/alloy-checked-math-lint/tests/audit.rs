@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use alloy_checked_math_lint::{Audit, AuditFormat, Config, EscapeHatchKind};
+
+const VAULT: &str = r#"
+struct Vault {
+    assets: u64,
+    shares: u64,
+}
+
+impl Vault {
+    // Share prices only grow, so the ratio is at least 1.
+    #[unchecked_fn]
+    fn ratio(&self) -> u64 {
+        self.assets / self.shares
+    }
+
+    #[checked_fn]
+    fn deposit(&mut self, amount: u64) -> Result<(), CheckedMathError> {
+        self.assets += amount;
+        self.shares = unchecked! { self.shares + 1 };
+        Ok(())
+    }
+
+    #[allow_unchecked_math(reason = "clamped to the cap by the caller")]
+    fn headroom(&self, cap: u64) -> u64 {
+        cap - self.assets.saturating_sub(1)
+    }
+
+    fn rollover(&self) -> u64 {
+        self.shares.wrapping_add(1) // checked-math: allow(epoch counter)
+    }
+}
+"#;
+
+fn audit(source: &str) -> Audit {
+    Audit::from_sources("src".as_ref(), [(PathBuf::from("src/vault.rs"), source.to_string())], &Config::default())
+}
+
+#[test]
+fn test_escape_hatches() {
+    let audit = audit(VAULT);
+
+    let hatches: Vec<_> = audit.escape_hatches.iter().map(|hatch| {
+        (hatch.span.start.line, hatch.kind, hatch.function.as_deref().unwrap(), hatch.arithmetic.join(", "), hatch.justification.as_deref())
+    }).collect();
+
+    assert_eq!(hatches, [
+        (9, EscapeHatchKind::UncheckedFn, "vault::Vault::ratio", "self.assets / self.shares".to_string(), Some("Share prices only grow, so the ratio is at least 1.")),
+        (17, EscapeHatchKind::UncheckedMacro, "vault::Vault::deposit", "self.shares + 1".to_string(), None),
+        (21, EscapeHatchKind::AllowAttribute, "vault::Vault::headroom", "cap - self.assets.saturating_sub(1)".to_string(), Some("clamped to the cap by the caller")),
+        (23, EscapeHatchKind::SaturatingMethod, "vault::Vault::headroom", "self.assets.saturating_sub(1)".to_string(), Some("clamped to the cap by the caller")),
+        (27, EscapeHatchKind::WrappingMethod, "vault::Vault::rollover", "self.shares.wrapping_add(1)".to_string(), Some("epoch counter")),
+        (27, EscapeHatchKind::AllowComment, "vault::Vault::rollover", "".to_string(), Some("epoch counter")),
+    ]);
+
+    assert_eq!(audit.escape_hatches[1].code, "unchecked! { self.shares + 1 }");
+    assert_eq!(audit.escape_hatches[5].code, "// checked-math: allow(epoch counter)");
+
+    let markdown = audit.format(AuditFormat::Markdown);
+    assert!(markdown.starts_with("# Escape hatches\n\nFound 6 escape hatches, 1 without a justification.\n"));
+    assert!(markdown.contains("| `vault.rs:17:23` | `vault::Vault::deposit` | `unchecked! { self.shares + 1 }` | `self.shares + 1` | **none** |\n"));
+
+    let json = audit.format(AuditFormat::Json);
+    assert_eq!(json.lines().count(), 6);
+    assert!(json.starts_with(r#"{"arithmetic":["self.assets / self.shares"],"code":"fn ratio","column":5,"#));
+}
+
+#[test]
+fn test_diff() {
+    let base = audit(VAULT);
+
+    // Moving code around and reformatting it is not a change, a new or edited escape hatch is.
+    let head = audit(&format!("\n\n{}", VAULT
+        .replace("unchecked! { self.shares + 1 }", "unchecked!{self.shares+1}")
+        .replace("self.shares.wrapping_add(1)", "self.shares.wrapping_add(2)")
+        .replace("cap - self", "unchecked! { cap - 1 } - self")));
+
+    let diff = head.diff(&base);
+    let added: Vec<_> = diff.added.iter().map(|hatch| (hatch.span.start.line, hatch.kind)).collect();
+    let removed: Vec<_> = diff.removed.iter().map(|hatch| (hatch.span.start.line, hatch.kind)).collect();
+
+    assert_eq!(added, [(25, EscapeHatchKind::UncheckedMacro), (29, EscapeHatchKind::WrappingMethod)]);
+    assert_eq!(removed, [(27, EscapeHatchKind::WrappingMethod)]);
+
+    let markdown = diff.format(AuditFormat::Markdown);
+    assert!(markdown.starts_with("# Escape hatch changes\n\n2 added, 1 removed.\n\n## Added\n\n"));
+    assert!(markdown.contains("\n## Removed\n\n"));
+    assert!(head.diff(&head).format(AuditFormat::Markdown).ends_with("No escape hatches added or removed.\n"));
+
+    assert!(diff.format(AuditFormat::Json).lines().all(|line| line.contains(r#""change":"added""#) || line.contains(r#""change":"removed""#)));
+}
@@ -0,0 +1,52 @@
+use alloy_checked_math_lint::{check_path, Baseline, Config};
+
+const LEDGER: &str = r#"
+fn credit(balance: u64, amount: u64, fee: u64) -> u64 {
+    balance + amount - fee
+}
+
+fn debit(balance: u64, amount: u64) -> u64 {
+    let remaining = balance - amount;
+    let _ = balance - amount;
+    remaining
+}
+"#;
+
+#[test]
+fn test_baseline() {
+    let dir = std::env::temp_dir().join(format!("checked-math-baseline-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("ledger.rs");
+
+    std::fs::write(&file, LEDGER).unwrap();
+    let baseline = Baseline::from_report(&check_path(&dir, &Config::default()));
+    assert_eq!((baseline.entries().count(), baseline.len()), (2, 3));
+
+    baseline.save(dir.join("baseline.json")).unwrap();
+    let baseline = Baseline::load(dir.join("baseline.json")).unwrap();
+
+    // Moved and reformatted code still matches, a new expression does not.
+    let shifted = LEDGER.replace("fn credit", "\n\n// Credits\nfn credit").replace("balance + amount - fee", "balance+amount-fee");
+    std::fs::write(&file, format!("{shifted}\nfn fee(amount: u64) -> u64 {{ amount / 100 }}\n")).unwrap();
+
+    let mut report = check_path(&dir, &Config::default());
+    let status = baseline.filter(&mut report);
+    assert_eq!(status.known, 3);
+    assert!(status.fixed.is_empty());
+    assert_eq!(report.violations.len(), 1);
+    assert_eq!(report.violations[0].expression, "amount / 100");
+    assert_eq!(report.files[0].violations, 1);
+
+    // Removing one of two identical expressions leaves a fixed entry to prune.
+    std::fs::write(&file, LEDGER.replace("    let _ = balance - amount;\n", "")).unwrap();
+
+    let mut report = check_path(&dir, &Config::default());
+    let status = baseline.filter(&mut report);
+    assert!(report.is_clean());
+    assert_eq!(status.known, 2);
+    assert_eq!(status.fixed.len(), 1);
+    assert_eq!((status.fixed[0].function.as_deref(), status.fixed[0].expression.as_str(), status.fixed[0].count), (Some("ledger::debit"), "balance-amount", 1));
+
+    assert!(Baseline::from_json(r#"{"version": 2, "violations": []}"#).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
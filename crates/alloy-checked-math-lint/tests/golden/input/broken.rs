@@ -0,0 +1,3 @@
+fn broken(amount: u64) -> {
+    amount
+}
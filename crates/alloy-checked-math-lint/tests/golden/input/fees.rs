@@ -0,0 +1,17 @@
+struct Pool {
+    reserve: u64,
+}
+
+impl Pool {
+    fn fee(&self, amount: u64, bps: u64) -> u64 {
+        amount * bps / 10_000
+    }
+
+    fn withdraw(&mut self, amount: u64) {
+        self.reserve -= amount;
+    }
+}
+
+fn delta(a: i64, b: i64) -> i64 {
+    -(a % b)
+}
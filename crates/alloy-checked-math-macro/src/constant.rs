@@ -0,0 +1,277 @@
+use syn::spanned::Spanned;
+
+// Wide enough for every intermediate result of 512 bit operands except products, whose overflow
+// here implies overflow of the operand type anyway.
+type Wide = alloy_primitives::Signed<1024, 16>;
+type WideRaw = alloy_primitives::Uint<1024, 16>;
+
+pub enum Folded {
+    NotConstant,
+    Value(syn::Expr),
+    Error(syn::Error),
+}
+
+#[derive(Clone)]
+struct IntType {
+    path: syn::Path,
+    bits: usize,
+    signed: bool,
+    primitive: bool,
+}
+
+impl IntType {
+    fn from_path(path: syn::Path) -> Option<Self> {
+        let name = path.segments.last()?.ident.to_string();
+
+        let (bits, signed, primitive) = match name.as_str() {
+            "u8" => (8, false, true),
+            "u16" => (16, false, true),
+            "u32" => (32, false, true),
+            "u64" => (64, false, true),
+            "u128" => (128, false, true),
+            "i8" => (8, true, true),
+            "i16" => (16, true, true),
+            "i32" => (32, true, true),
+            "i64" => (64, true, true),
+            "i128" => (128, true, true),
+            name => {
+                let signed = name.starts_with('I');
+                let bits = name.strip_prefix(['U', 'I'])?.parse().ok().filter(|&bits| 0 < bits && bits <= 512)?;
+                (bits, signed, false)
+            },
+        };
+
+        Some(IntType { path, bits, signed, primitive })
+    }
+
+    fn same(&self, other: &IntType) -> bool {
+        self.bits == other.bits && self.signed == other.signed && self.primitive == other.primitive
+    }
+
+    fn name(&self) -> String {
+        self.path.segments.last().unwrap().ident.to_string()
+    }
+
+    fn bounds(&self) -> (Wide, Wide) {
+        if self.signed {
+            let half = WideRaw::ONE << (self.bits - 1);
+            return (-Wide::from_raw(half), Wide::from_raw(half - WideRaw::ONE));
+        }
+
+        (Wide::ZERO, Wide::from_raw((WideRaw::ONE << self.bits) - WideRaw::ONE))
+    }
+
+    fn contains(&self, value: Wide) -> bool {
+        let (min, max) = self.bounds();
+        min <= value && value <= max
+    }
+}
+
+fn is_foldable_binary_op(op: syn::BinOp) -> bool {
+    matches!(op,
+        | syn::BinOp::Add(_)
+        | syn::BinOp::Sub(_)
+        | syn::BinOp::Mul(_)
+        | syn::BinOp::Div(_)
+        | syn::BinOp::Rem(_)
+    )
+}
+
+// `T::MAX`, `T::MIN`, `T::ZERO` and `T::ONE` for primitive and alloy integer types.
+fn associated_const(path: &syn::ExprPath) -> Option<(IntType, syn::Ident)> {
+    if path.qself.is_some() || path.path.segments.len() < 2 || path.path.segments.iter().any(|segment| !segment.arguments.is_none()) {
+        return None;
+    }
+
+    let mut ty = path.path.clone();
+    let name = ty.segments.pop()?.into_value().ident;
+    ty.segments.pop_punct();
+    let ty = IntType::from_path(ty)?;
+
+    return match name.to_string().as_str() {
+        "MAX" | "MIN" => Some((ty, name)),
+        "ZERO" | "ONE" if !ty.primitive => Some((ty, name)),
+        _ => None,
+    };
+}
+
+// The integer type of a literal-only expression: `None` if it is not constant or mixes types,
+// `Some(None)` if it only consists of unsuffixed literals whose type comes from the context.
+fn constant_type(expr: &syn::Expr) -> Option<Option<IntType>> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) => match lit.suffix() {
+            "" => Some(None),
+            suffix => {
+                let ty = IntType::from_path(syn::Ident::new(suffix, lit.span()).into())?;
+                ty.primitive.then_some(Some(ty))
+            },
+        },
+        syn::Expr::Path(path) => associated_const(path).map(|(ty, _)| Some(ty)),
+        syn::Expr::Paren(paren) => constant_type(&paren.expr),
+        syn::Expr::Group(group) => constant_type(&group.expr),
+        syn::Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => match constant_type(expr)? {
+            Some(ty) if !ty.signed => None,
+            ty => Some(ty),
+        },
+        syn::Expr::Binary(binary) if is_foldable_binary_op(binary.op) => {
+            match (constant_type(&binary.left)?, constant_type(&binary.right)?) {
+                (Some(left), Some(right)) => left.same(&right).then_some(Some(left)),
+                // Alloy integers have no literals, so an unsuffixed literal next to one is not constant.
+                (Some(ty), None) | (None, Some(ty)) => ty.primitive.then_some(Some(ty)),
+                (None, None) => Some(None),
+            }
+        },
+        _ => None,
+    }
+}
+
+fn overflow_error<T: quote::ToTokens>(span: proc_macro2::Span, expr: &T, ty: &IntType) -> syn::Error {
+    let message = format!("constant arithmetic `{}` overflows `{}`", expr.to_token_stream(), ty.name());
+    syn::Error::new(span, message)
+}
+
+fn evaluate_literal(lit: &syn::LitInt, negative: bool, ty: &IntType) -> syn::Result<Wide> {
+    let value = Wide::from_dec_str(lit.base10_digits()).ok().map(|value| if negative { -value } else { value });
+
+    match value {
+        Some(value) if ty.contains(value) => Ok(value),
+        _ => Err(syn::Error::new(lit.span(), format!("literal out of range for `{}`", ty.name()))),
+    }
+}
+
+fn evaluate(expr: &syn::Expr, ty: &IntType) -> syn::Result<Wide> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) => evaluate_literal(lit, false, ty),
+        syn::Expr::Path(path) => {
+            let (_, name) = associated_const(path).unwrap();
+            let (min, max) = ty.bounds();
+
+            return Ok(match name.to_string().as_str() {
+                "MAX" => max,
+                "MIN" => min,
+                "ZERO" => Wide::ZERO,
+                _ => Wide::ONE,
+            });
+        },
+        syn::Expr::Paren(paren) => evaluate(&paren.expr, ty),
+        syn::Expr::Group(group) => evaluate(&group.expr, ty),
+        syn::Expr::Unary(unary) => {
+            // Like rustc, `-128i8` is a single negative literal rather than the negation of `128i8`.
+            if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) = &*unary.expr {
+                return evaluate_literal(lit, true, ty);
+            }
+
+            let value = evaluate(&unary.expr, ty)?;
+            value.checked_neg().filter(|&value| ty.contains(value)).ok_or_else(|| overflow_error(unary.op.span(), unary, ty))
+        },
+        syn::Expr::Binary(binary) => {
+            let (left, right) = (evaluate(&binary.left, ty)?, evaluate(&binary.right, ty)?);
+
+            let value = match binary.op {
+                syn::BinOp::Add(_) => left.checked_add(right),
+                syn::BinOp::Sub(_) => left.checked_sub(right),
+                syn::BinOp::Mul(_) => left.checked_mul(right),
+                _ if right.is_zero() => {
+                    let message = format!("constant arithmetic `{}` divides by zero", quote::ToTokens::to_token_stream(binary));
+                    return Err(syn::Error::new(binary.op.span(), message));
+                },
+                syn::BinOp::Div(_) => left.checked_div(right),
+                // Like `checked_rem`, `MIN % -1` overflows because `MIN / -1` does.
+                _ => left.checked_div(right).filter(|&quotient| ty.contains(quotient)).and(left.checked_rem(right)),
+            };
+
+            value.filter(|&value| ty.contains(value)).ok_or_else(|| overflow_error(binary.op.span(), binary, ty))
+        },
+        _ => unreachable!("`constant_type` only accepts literals, constants and arithmetic"),
+    }
+}
+
+fn literal(value: Wide, ty: &IntType, span: proc_macro2::Span) -> syn::Expr {
+    if ty.primitive {
+        let lit = syn::LitInt::new(&format!("{}{}", value.unsigned_abs(), ty.name()), span);
+
+        return match value.is_negative() {
+            true => syn::parse_quote_spanned! { span => -#lit },
+            false => syn::parse_quote_spanned! { span => #lit },
+        };
+    }
+
+    let mut limbs = value.into_raw().as_limbs()[..ty.bits.div_ceil(64)].to_vec();
+
+    if let (Some(last), 1..) = (limbs.last_mut(), ty.bits % 64) {
+        *last &= (1 << (ty.bits % 64)) - 1;
+    }
+
+    let path = &ty.path;
+    let limbs = limbs.into_iter().map(|limb| syn::LitInt::new(&format!("{limb:#x}"), span));
+    syn::parse_quote_spanned! { span => #path::from_limbs([#(#limbs),*]) }
+}
+
+// Evaluates arithmetic over literals and integer constants at expansion time: provable overflow and
+// division by zero become compile errors, anything else becomes a single literal.
+pub fn fold(expr: &syn::Expr, span: proc_macro2::Span) -> Folded {
+    let Some(Some(ty)) = constant_type(expr) else {
+        return Folded::NotConstant;
+    };
+
+    match evaluate(expr, &ty) {
+        Ok(value) => Folded::Value(literal(value, &ty, span)),
+        Err(error) => Folded::Error(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    fn folded(source: &str) -> Option<String> {
+        match fold(&syn::parse_str(source).unwrap(), proc_macro2::Span::call_site()) {
+            Folded::NotConstant => None,
+            Folded::Value(expr) => Some(expr.to_token_stream().to_string()),
+            Folded::Error(error) => Some(error.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_fold_primitives() {
+        assert_eq!(folded("10u8 * 20").as_deref(), Some("200u8"));
+        assert_eq!(folded("-(i8::MAX) - 1").as_deref(), Some("- 128i8"));
+        assert_eq!(folded("-128i8").as_deref(), Some("- 128i8"));
+        assert_eq!(folded("-128i8 + 1").as_deref(), Some("- 127i8"));
+        assert_eq!(folded("-170141183460469231731687303715884105728i128").as_deref(), Some("- 170141183460469231731687303715884105728i128"));
+        assert_eq!(folded("7i32 % -3").as_deref(), Some("1i32"));
+        assert_eq!(folded("i8::MIN % 1").as_deref(), Some("0i8"));
+        assert_eq!(folded("-7i32 / 2").as_deref(), Some("- 3i32"));
+        assert_eq!(folded("u128::MAX / 0x10").as_deref(), Some("21267647932558653966460912964485513215u128"));
+
+        assert_eq!(folded("10u8 * 30").as_deref(), Some("constant arithmetic `10u8 * 30` overflows `u8`"));
+        assert_eq!(folded("-i8::MIN").as_deref(), Some("constant arithmetic `- i8 :: MIN` overflows `i8`"));
+        assert_eq!(folded("i8::MIN / -1").as_deref(), Some("constant arithmetic `i8 :: MIN / - 1` overflows `i8`"));
+        assert_eq!(folded("i8::MIN % -1").as_deref(), Some("constant arithmetic `i8 :: MIN % - 1` overflows `i8`"));
+        assert_eq!(folded("1u32 / (2 - 2)").as_deref(), Some("constant arithmetic `1u32 / (2 - 2)` divides by zero"));
+        assert_eq!(folded("300 + 1u8").as_deref(), Some("literal out of range for `u8`"));
+        assert_eq!(folded("-129i8").as_deref(), Some("literal out of range for `i8`"));
+        assert_eq!(folded("128i8 - 1").as_deref(), Some("literal out of range for `i8`"));
+
+        assert_eq!(folded("x + 1u8"), None);
+        assert_eq!(folded("1 + 2"), None);
+        assert_eq!(folded("1u8 + 1u16"), None);
+        assert_eq!(folded("-1u8"), None);
+        assert_eq!(folded("usize::MAX - 1"), None);
+    }
+
+    #[test]
+    fn test_fold_alloy() {
+        let max = "0xffffffffffffffff";
+
+        assert_eq!(folded("U256::MAX - U256::ONE"), Some(format!("U256 :: from_limbs ([0xfffffffffffffffe , {max} , {max} , {max}])")));
+        assert_eq!(folded("I256::MIN + I256::ONE"), Some("I256 :: from_limbs ([0x1 , 0x0 , 0x0 , 0x8000000000000000])".to_string()));
+        assert_eq!(folded("-I128::ONE"), Some(format!("I128 :: from_limbs ([{max} , {max}])")));
+        assert_eq!(folded("U24::MAX - U24::ONE").as_deref(), Some("U24 :: from_limbs ([0xfffffe])"));
+
+        assert_eq!(folded("U256::MAX + U256::ONE").as_deref(), Some("constant arithmetic `U256 :: MAX + U256 :: ONE` overflows `U256`"));
+        assert_eq!(folded("U256::MAX + 1"), None);
+        assert_eq!(folded("U256::MAX + U128::ONE"), None);
+    }
+}
@@ -1,7 +1,90 @@
 use quote::ToTokens;
 use syn::fold::Fold;
+use syn::spanned::Spanned;
 
-struct CheckedTransformer;
+mod constant;
+
+#[derive(Clone)]
+enum Propagation {
+    Result,
+    Option,
+    Checked,
+    MapErr(syn::Expr),
+    Break(syn::Lifetime),
+    Panic,
+}
+
+fn return_type_ident(output: &syn::ReturnType) -> Option<&syn::Ident> {
+    match output {
+        syn::ReturnType::Type(_, ty) => match &**ty {
+            syn::Type::Path(ty) => ty.path.segments.last().map(|segment| &segment.ident),
+            _ => None,
+        },
+        syn::ReturnType::Default => None,
+    }
+}
+
+impl Propagation {
+    // Picks how a failed operation leaves a function or closure with the given return type.
+    fn for_return_type(&self, output: &syn::ReturnType) -> Propagation {
+        match (self, return_type_ident(output)) {
+            (Propagation::Panic, _) => Propagation::Panic,
+            (_, Some(ident)) if ident == "Option" => Propagation::Option,
+            (_, Some(ident)) if ident == "Checked" => Propagation::Checked,
+            (Propagation::MapErr(map_err), _) => Propagation::MapErr(map_err.clone()),
+            _ => Propagation::Result,
+        }
+    }
+}
+
+struct CheckedTransformer {
+    propagation: Propagation,
+    in_const: bool,
+    namespace: syn::Path,
+    macros: Vec<syn::Ident>,
+    errors: Vec<syn::Error>,
+}
+
+#[derive(Default)]
+struct ArithmeticFinder {
+    span: Option<proc_macro2::Span>,
+}
+
+impl<'ast> syn::visit::Visit<'ast> for ArithmeticFinder {
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        if self.span.is_none() && (is_checked_binary_op(node.op) || is_checked_binary_assign_op(node.op)) {
+            self.span = Some(node.op.span());
+        }
+
+        syn::visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_expr_unary(&mut self, node: &'ast syn::ExprUnary) {
+        if self.span.is_none() && is_checked_unary_op(node.op) {
+            self.span = Some(node.op.span());
+        }
+
+        syn::visit::visit_expr_unary(self, node);
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+fn find_arithmetic(expr: &syn::Expr) -> Option<proc_macro2::Span> {
+    let mut finder = ArithmeticFinder::default();
+    syn::visit::Visit::visit_expr(&mut finder, expr);
+    finder.span
+}
+
+fn is_unsuffixed_literal(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) => lit.suffix().is_empty(),
+        syn::Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => is_unsuffixed_literal(expr),
+        syn::Expr::Paren(paren) => is_unsuffixed_literal(&paren.expr),
+        syn::Expr::Group(group) => is_unsuffixed_literal(&group.expr),
+        _ => false,
+    }
+}
 
 fn is_checked_binary_op(op: syn::BinOp) -> bool {
     matches!(op,
@@ -29,46 +112,361 @@ fn is_checked_unary_op(op: syn::UnOp) -> bool {
     )
 }
 
-fn namespace() -> syn::Path {
-    #[cfg(feature = "overridden_math")]
-    syn::parse_quote! { self }
+const FACADE_PACKAGE: &str = "alloy-checked-math";
+const DEFAULT_NAMESPACE: &str = "::alloy_checked_math";
+
+// Path under which the crate being compiled reaches the facade: an explicit
+// `[package.metadata.alloy-checked-math] crate = "..."` or the (possibly renamed) dependency on the
+// facade, looked up in `[workspace.dependencies]` of the workspace manifest when it is inherited. The
+// facade's own tests, examples and doctests share its manifest and reach it under its default name.
+fn manifest_namespace(manifest: &toml::Table, workspace: Option<&toml::Table>) -> Option<String> {
+    let package = manifest.get("package").and_then(toml::Value::as_table);
+    let metadata = package.and_then(|package| package.get("metadata")?.get(FACADE_PACKAGE)?.get("crate")?.as_str());
+
+    if let Some(path) = metadata {
+        return Some(path.to_string());
+    }
+
+    let targets = manifest.get("target").and_then(toml::Value::as_table).into_iter().flat_map(|targets| targets.values());
+    let scopes = std::iter::once(manifest).chain(targets.filter_map(toml::Value::as_table));
+
+    let dependencies = scopes.flat_map(|scope| {
+        ["dependencies", "dev-dependencies", "build-dependencies"].into_iter().filter_map(|kind| scope.get(kind)?.as_table())
+    });
+
+    let inherited = workspace.and_then(|workspace| workspace.get("workspace")?.get("dependencies")?.as_table());
 
-    #[cfg(not(feature = "overridden_math"))]
-    syn::parse_quote! { alloy_checked_math }
+    return dependencies.flatten().find_map(|(name, dependency)| {
+        let dependency = match dependency.get("workspace").and_then(toml::Value::as_bool) {
+            Some(true) => inherited.and_then(|inherited| inherited.get(name)).unwrap_or(dependency),
+            _ => dependency,
+        };
+        let package = dependency.get("package").and_then(toml::Value::as_str).unwrap_or(name);
+        (package == FACADE_PACKAGE).then(|| format!("::{}", name.replace('-', "_")))
+    });
 }
 
-fn checked_operand<T: ToTokens>(operand: T) -> syn::Expr {
-    let ns = namespace();
-    syn::parse_quote! { #ns::CheckedPack::pack(#operand) }
+// The nearest manifest with a `[workspace]` table in the directory of the crate or its parents.
+fn workspace_manifest(manifest_dir: &std::path::Path) -> Option<toml::Table> {
+    return manifest_dir.ancestors().find_map(|dir| {
+        let manifest: toml::Table = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?.parse().ok()?;
+        manifest.contains_key("workspace").then_some(manifest)
+    });
 }
 
-fn tried_expr<T: ToTokens>(expr: T) -> syn::Expr {
-    let ns = namespace();
-    syn::parse_quote! { #ns::CheckedUnpack::unpack(#[allow(unused_parens)] (#expr))? }
+thread_local! {
+    static NAMESPACES: std::cell::RefCell<std::collections::HashMap<String, Option<String>>> = Default::default();
 }
 
-fn checked_unary_expr(mut expr: syn::ExprUnary) -> syn::Expr {
-    *expr.expr = checked_operand(*expr.expr);
-    tried_expr(expr)
+fn namespace() -> syn::Result<syn::Path> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+
+    let namespace = NAMESPACES.with(|namespaces| {
+        let mut namespaces = namespaces.borrow_mut();
+        let namespace = namespaces.entry(manifest_dir).or_insert_with_key(|manifest_dir| {
+            let manifest_dir = std::path::Path::new(manifest_dir);
+            let manifest = std::fs::read_to_string(manifest_dir.join("Cargo.toml")).ok()?;
+            manifest_namespace(&manifest.parse().ok()?, workspace_manifest(manifest_dir).as_ref())
+        });
+        namespace.clone()
+    });
+
+    let namespace = namespace.as_deref().unwrap_or(DEFAULT_NAMESPACE);
+
+    return syn::parse_str(namespace).map_err(|_| {
+        let message = format!("invalid crate path `{namespace}` in `package.metadata.{FACADE_PACKAGE}`");
+        syn::Error::new(proc_macro2::Span::call_site(), message)
+    });
 }
 
-fn checked_binary_expr(mut expr: syn::ExprBinary) -> syn::Expr {
-    *expr.left = checked_operand(*expr.left);
-    *expr.right = checked_operand(*expr.right);
-    tried_expr(expr)
+fn spanned_namespace(ns: &syn::Path, span: proc_macro2::Span) -> syn::Path {
+    let mut ns = ns.clone();
+
+    if let Some(leading_colon) = &mut ns.leading_colon {
+        leading_colon.spans = [span; 2];
+    }
+
+    for segment in &mut ns.segments {
+        segment.ident.set_span(span);
+    }
+
+    ns
+}
+
+// Generated tokens are located at the operand or operator they stand for, so type errors point at
+// the offending part of the expression rather than at the whole macro invocation. They keep the
+// call site hygiene, so lints still see them as macro output.
+fn located_span(span: proc_macro2::Span) -> proc_macro2::Span {
+    proc_macro2::Span::call_site().located_at(span)
+}
+
+fn checked_operand<T: ToTokens>(ns: &syn::Path, operand: T) -> syn::Expr {
+    let span = located_span(operand.span());
+    let ns = spanned_namespace(ns, span);
+    syn::parse_quote_spanned! { span => #ns::CheckedPack::pack(#operand) }
+}
+
+fn tried_expr<T: ToTokens>(ns: &syn::Path, expr: T, propagation: &Propagation, span: proc_macro2::Span) -> syn::Expr {
+    let span = located_span(span);
+    let ns = spanned_namespace(ns, span);
+
+    match propagation {
+        Propagation::Option => syn::parse_quote_spanned! { span => #ns::CheckedUnpack::unpack(#[allow(unused_parens)] (#expr)).ok()? },
+        Propagation::Checked => syn::parse_quote_spanned! { span => (#expr)? },
+        Propagation::MapErr(map_err) => syn::parse_quote_spanned! { span => #ns::CheckedUnpack::unpack(#[allow(unused_parens)] (#expr)).map_err(#map_err)? },
+        // The `break` value is parenthesized, `syn` would read `'label ::path` as a labeled block.
+        Propagation::Break(label) => {
+            let (value, err) = (hygienic_ident("value"), hygienic_ident("err"));
+            syn::parse_quote_spanned! { span =>
+                match #ns::CheckedUnpack::unpack(#[allow(unused_parens)] (#expr)) {
+                    ::core::result::Result::Ok(#value) => #value,
+                    ::core::result::Result::Err(#err) => break #label (::core::result::Result::Err(#err)),
+                }
+            }
+        },
+        _ => syn::parse_quote_spanned! { span => #ns::CheckedUnpack::unpack(#[allow(unused_parens)] (#expr))? },
+    }
 }
 
-fn checked_binary_assign_expr(expr: syn::ExprBinary) -> syn::Expr {
-    let left = expr.left.clone();
+fn unpacked_expr<T: ToTokens>(ns: &syn::Path, expr: T) -> syn::Expr {
+    syn::parse_quote! { #ns::CheckedUnpack::unpack(#expr) }
+}
+
+fn hygienic_ident(name: &str) -> syn::Ident {
+    syn::Ident::new(name, proc_macro2::Span::mixed_site())
+}
+
+fn without_parens(expr: syn::Expr) -> syn::Expr {
+    match expr {
+        syn::Expr::Paren(paren) => without_parens(*paren.expr),
+        expr => expr,
+    }
+}
+
+fn operator_str<T: ToTokens>(op: T) -> String {
+    op.to_token_stream().to_string()
+}
+
+impl CheckedTransformer {
+    fn new(propagation: Propagation) -> Self {
+        let (namespace, errors) = match namespace() {
+            Ok(namespace) => (namespace, Vec::new()),
+            Err(error) => (syn::parse_str(DEFAULT_NAMESPACE).unwrap(), vec![error]),
+        };
+
+        CheckedTransformer { propagation, in_const: false, namespace, macros: Vec::new(), errors }
+    }
+
+    fn with_namespace(mut self, namespace: Option<syn::Path>) -> Self {
+        if let Some(namespace) = namespace {
+            self.namespace = namespace;
+        }
+
+        self
+    }
+
+    fn with_macros(mut self, macros: Vec<syn::Ident>) -> Self {
+        self.macros = macros;
+        self
+    }
+
+    fn with_const(mut self, in_const: bool) -> Self {
+        self.in_const = in_const;
+        self
+    }
+
+    // Local items get their own propagation from their signature: `?` in a nested fn returns
+    // from that fn, and only a `const fn` body must stay const-evaluable.
+    fn enter_fn(&mut self, sig: &syn::Signature) -> (Propagation, bool) {
+        let propagation = self.propagation.for_return_type(&sig.output);
+        let outer = std::mem::replace(&mut self.propagation, propagation);
+        (outer, std::mem::replace(&mut self.in_const, sig.constness.is_some()))
+    }
+
+    fn leave_fn(&mut self, (propagation, in_const): (Propagation, bool)) {
+        self.propagation = propagation;
+        self.in_const = in_const;
+    }
+
+    // `Checked<T>` operators and `?` are not const, so const code calls the primitive `checked_*`
+    // methods and leaves through `return`, `break` or a const panic instead.
+    fn const_checked_expr(&self, op: &dyn ToTokens, operands: Vec<syn::Expr>) -> syn::Expr {
+        let span = located_span(op.span());
+        let ns = spanned_namespace(&self.namespace, span);
+        let operator = operator_str(op);
+
+        let (method, variant) = match operator.as_str() {
+            "+" => ("checked_add", "Add"),
+            "-" if operands.len() == 1 => ("checked_neg", "Neg"),
+            "-" => ("checked_sub", "Sub"),
+            "*" => ("checked_mul", "Mul"),
+            "/" => ("checked_div", "Div"),
+            _ => ("checked_rem", "Rem"),
+        };
+
+        let (method, variant) = (syn::Ident::new(method, span), syn::Ident::new(variant, span));
+        let error: syn::Expr = syn::parse_quote_spanned! { span => #ns::CheckedMathError::#variant };
+
+        let leave: syn::Expr = match &self.propagation {
+            Propagation::Result => syn::parse_quote_spanned! { span => return ::core::result::Result::Err(#error) },
+            Propagation::Option => syn::parse_quote_spanned! { span => return ::core::option::Option::None },
+            Propagation::Checked => syn::parse_quote_spanned! { span => return #ns::Checked::Err(#error) },
+            Propagation::MapErr(map_err) => syn::parse_quote_spanned! { span => return ::core::result::Result::Err(#map_err(#error)) },
+            Propagation::Break(label) => syn::parse_quote_spanned! { span => break #label (::core::result::Result::Err(#error)) },
+            Propagation::Panic => {
+                let message = format!("checked math: `{operator}` failed with {variant} in a const context");
+                syn::parse_quote_spanned! { span => panic!(#message) }
+            },
+        };
+
+        let call: syn::Expr = match &operands[..] {
+            // An unsuffixed literal has no type to look `checked_*` up on yet, so it takes the type
+            // of the right operand through an array.
+            [left, right] if is_unsuffixed_literal(left) => {
+                let (left_value, right_value) = (hygienic_ident("left"), hygienic_ident("right"));
+                syn::parse_quote_spanned! { span =>
+                    match (#left, #right) {
+                        (#left_value, #right_value) => [#right_value, #left_value][1].#method(#right_value),
+                    }
+                }
+            },
+            [receiver, args @ ..] => syn::parse_quote_spanned! { span => (#receiver).#method(#(#args),*) },
+            [] => unreachable!("operators have operands"),
+        };
+
+        let value = hygienic_ident("value");
 
-    let unassigned_binary = {
-        let mut unassigned_binary = expr.clone();
-        unassigned_binary.op = map_assign_op(expr.op);
-        checked_binary_expr(unassigned_binary)
-    };
+        syn::parse_quote_spanned! { span =>
+            match #call {
+                ::core::option::Option::Some(#value) => #value,
+                ::core::option::Option::None => #leave,
+            }
+        }
+    }
 
-    syn::parse_quote! {
-        #left = #unassigned_binary
+    fn finish<T: ToTokens>(self, output: T) -> proc_macro::TokenStream {
+        let errors = self.errors.into_iter().map(|error| error.to_compile_error());
+        return quote::quote! { #(#errors)* #output }.into();
+    }
+
+    // `?` inside a closure or an async block returns from that closure or block, not from the
+    // enclosing function, so checked arithmetic there is only allowed where the closure itself
+    // declares what it returns.
+    fn fold_boundary_expr(&mut self, expr: syn::Expr) -> syn::Expr {
+        if let Propagation::Panic = self.propagation {
+            return syn::fold::fold_expr(self, expr);
+        }
+
+        match expr {
+            syn::Expr::Closure(closure) if !matches!(closure.output, syn::ReturnType::Default) => {
+                let propagation = self.propagation.for_return_type(&closure.output);
+                let outer = std::mem::replace(&mut self.propagation, propagation);
+                let closure = self.fold_expr_closure(closure);
+                self.propagation = outer;
+                syn::Expr::Closure(closure)
+            },
+
+            expr => {
+                if let Some(span) = find_arithmetic(&expr) {
+                    let message = match expr {
+                        syn::Expr::Closure(_) => "checked arithmetic inside a closure would return from the closure with `?`; \
+                            use `checked_closure!` or give the closure an explicit `Result` or `Option` return type",
+                        _ => "checked arithmetic inside an async block would return from the block with `?`; \
+                            move it into an `async fn` marked `#[checked_fn]`",
+                    };
+
+                    self.errors.push(syn::Error::new(span, message));
+                }
+
+                expr
+            },
+        }
+    }
+
+    fn checked_unary_expr(&self, mut expr: syn::ExprUnary) -> syn::Expr {
+        if self.in_const {
+            return self.const_checked_expr(&expr.op, vec![without_parens(*expr.expr)]);
+        }
+
+        match self.propagation {
+            Propagation::Panic => {
+                let ns = spanned_namespace(&self.namespace, expr.op.span());
+                let operand = without_parens(*expr.expr);
+                let operator = operator_str(expr.op);
+                let operand_ident = hygienic_ident("operand");
+
+                *expr.expr = checked_operand(&self.namespace, &operand_ident);
+                let unpacked = unpacked_expr(&self.namespace, &expr);
+
+                syn::parse_quote_spanned! { expr.op.span() =>
+                    #ns::panicking::unary(#operator, #operand, |#operand_ident| #unpacked)
+                }
+            },
+
+            ref propagation => {
+                let span = expr.op.span();
+                *expr.expr = checked_operand(&self.namespace, *expr.expr);
+                tried_expr(&self.namespace, expr, propagation, span)
+            },
+        }
+    }
+
+    fn checked_binary_expr(&self, mut expr: syn::ExprBinary) -> syn::Expr {
+        if self.in_const {
+            return self.const_checked_expr(&expr.op, vec![without_parens(*expr.left), without_parens(*expr.right)]);
+        }
+
+        match self.propagation {
+            Propagation::Panic => {
+                let ns = spanned_namespace(&self.namespace, expr.op.span());
+                let (left, right) = (without_parens(*expr.left), without_parens(*expr.right));
+                let operator = operator_str(expr.op);
+                let (left_ident, right_ident) = (hygienic_ident("left"), hygienic_ident("right"));
+
+                *expr.left = checked_operand(&self.namespace, &left_ident);
+                *expr.right = checked_operand(&self.namespace, &right_ident);
+                let unpacked = unpacked_expr(&self.namespace, &expr);
+
+                syn::parse_quote_spanned! { expr.op.span() =>
+                    #ns::panicking::binary(#operator, #left, #right, |#left_ident, #right_ident| #unpacked)
+                }
+            },
+
+            ref propagation => {
+                let span = expr.op.span();
+                *expr.left = checked_operand(&self.namespace, *expr.left);
+                *expr.right = checked_operand(&self.namespace, *expr.right);
+                tried_expr(&self.namespace, expr, propagation, span)
+            },
+        }
+    }
+
+    // `place op= value` must evaluate `place` once, so anything but a plain variable is borrowed
+    // through a `&mut` temporary. As for primitive compound assignment, `value` is evaluated first.
+    fn checked_binary_assign_expr(&self, mut expr: syn::ExprBinary) -> syn::Expr {
+        expr.op = map_assign_op(expr.op);
+
+        if let syn::Expr::Path(_) = *expr.left {
+            let left = expr.left.clone();
+            let binary = self.checked_binary_expr(expr);
+
+            return syn::parse_quote! {
+                #left = #binary
+            };
+        }
+
+        let (place, value) = (hygienic_ident("place"), hygienic_ident("value"));
+        let left = std::mem::replace(&mut *expr.left, syn::parse_quote! { *#place });
+        let right = std::mem::replace(&mut *expr.right, syn::parse_quote! { #value });
+        let binary = self.checked_binary_expr(expr);
+
+        syn::parse_quote! {
+            {
+                let #value = #right;
+                let #place = &mut #left;
+                *#place = #binary;
+            }
+        }
     }
 }
 
@@ -83,19 +481,236 @@ fn map_assign_op(op: syn::BinOp) -> syn::BinOp {
     }
 }
 
+// Items carrying one of these attributes are expanded (or deliberately left alone) by their own
+// attribute, so an enclosing `#[checked_impl]`/`#[checked_mod]` must not transform them again.
+fn has_own_checked_attr(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let ident = attr.path().segments.last().map(|segment| &segment.ident);
+        ident.is_some_and(|ident| ident == "checked_fn" || ident == "unchecked_fn" || ident == "checked_impl" || ident == "checked_mod")
+    })
+}
+
+// Standard macros whose arguments are plain comma separated expressions (format strings and
+// `name = value` arguments included). `vec!` and `matches!` have their own grammar.
+const EXPR_MACROS: &[&str] = &[
+    "assert", "assert_eq", "assert_ne", "debug_assert", "debug_assert_eq", "debug_assert_ne", "dbg",
+    "format", "format_args", "print", "println", "eprint", "eprintln", "write", "writeln", "panic",
+];
+
+// Expanded on their own, so never transformed again from the outside.
+const CHECKED_MACROS: &[&str] = &["checked", "checked_or_panic", "const_checked", "try_checked", "checked_closure", "unchecked"];
+
+enum MacroArgs {
+    List(syn::punctuated::Punctuated<syn::Expr, syn::Token![,]>),
+    Repeat(syn::Expr, syn::Token![;], syn::Expr),
+    Matches(syn::Expr, syn::Token![,], syn::Pat, Option<(syn::Token![if], Box<syn::Expr>)>),
+}
+
+impl MacroArgs {
+    fn parse_list(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(MacroArgs::List(syn::punctuated::Punctuated::parse_terminated(input)?))
+    }
+
+    fn parse_vec(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let fork = input.fork();
+
+        if fork.parse::<syn::Expr>().is_err() || !fork.peek(syn::Token![;]) {
+            return MacroArgs::parse_list(input);
+        }
+
+        Ok(MacroArgs::Repeat(input.parse()?, input.parse()?, input.parse()?))
+    }
+
+    fn parse_matches(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let expr = input.parse()?;
+        let comma = input.parse()?;
+        let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
+
+        let guard = match input.peek(syn::Token![if]) {
+            true => Some((input.parse()?, input.parse()?)),
+            false => None,
+        };
+
+        input.parse::<Option<syn::Token![,]>>()?;
+        Ok(MacroArgs::Matches(expr, comma, pat, guard))
+    }
+}
+
+impl ToTokens for MacroArgs {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            MacroArgs::List(exprs) => exprs.to_tokens(tokens),
+            MacroArgs::Repeat(expr, semi, len) => tokens.extend(quote::quote! { #expr #semi #len }),
+            MacroArgs::Matches(expr, comma, pat, guard) => {
+                tokens.extend(quote::quote! { #expr #comma #pat });
+
+                if let Some((if_token, guard)) = guard {
+                    tokens.extend(quote::quote! { #if_token #guard });
+                }
+            },
+        }
+    }
+}
+
+impl CheckedTransformer {
+    // Arguments of allowlisted macros are parsed as expressions and transformed like any other
+    // code. Everything else, including invocations that fail to parse, is left untouched.
+    fn fold_macro_args(&mut self, mut mac: syn::Macro) -> syn::Macro {
+        let Some(ident) = mac.path.segments.last().map(|segment| segment.ident.clone()) else {
+            return mac;
+        };
+
+        let name = ident.to_string();
+
+        let parsed = match name.as_str() {
+            name if CHECKED_MACROS.contains(&name) => return mac,
+            "vec" => mac.parse_body_with(MacroArgs::parse_vec),
+            "matches" => mac.parse_body_with(MacroArgs::parse_matches),
+            name if EXPR_MACROS.contains(&name) || self.macros.contains(&ident) => mac.parse_body_with(MacroArgs::parse_list),
+            _ => return mac,
+        };
+
+        let Ok(args) = parsed else {
+            return mac;
+        };
+
+        let args = match args {
+            MacroArgs::List(exprs) => MacroArgs::List(exprs.into_iter().map(|expr| self.fold_expr(expr)).collect()),
+            MacroArgs::Repeat(expr, semi, len) => MacroArgs::Repeat(self.fold_expr(expr), semi, self.fold_expr(len)),
+            MacroArgs::Matches(expr, comma, pat, guard) => {
+                let guard = guard.map(|(if_token, guard)| (if_token, Box::new(self.fold_expr(*guard))));
+                MacroArgs::Matches(self.fold_expr(expr), comma, pat, guard)
+            },
+        };
+
+        mac.tokens = args.into_token_stream();
+        mac
+    }
+}
+
 impl Fold for CheckedTransformer {
+    fn fold_item(&mut self, i: syn::Item) -> syn::Item {
+        match i {
+            syn::Item::Fn(i) => syn::Item::Fn(self.fold_item_fn(i)),
+            syn::Item::Impl(i) if !has_own_checked_attr(&i.attrs) => syn::Item::Impl(syn::fold::fold_item_impl(self, i)),
+            syn::Item::Trait(i) if !has_own_checked_attr(&i.attrs) => syn::Item::Trait(syn::fold::fold_item_trait(self, i)),
+            syn::Item::Mod(i) if !has_own_checked_attr(&i.attrs) => syn::Item::Mod(syn::fold::fold_item_mod(self, i)),
+            syn::Item::Const(i) => syn::Item::Const(self.fold_item_const(i)),
+            syn::Item::Static(i) => syn::Item::Static(self.fold_item_static(i)),
+            i => i,
+        }
+    }
+
+    fn fold_type(&mut self, i: syn::Type) -> syn::Type {
+        i
+    }
+
+    // `const`/`static` initializers, array lengths, inline `const` blocks and const generic
+    // arguments are evaluated at compile time, where overflow is already an error. Only the
+    // closures they create are folded, see `fold_const_closures`.
+    fn fold_generic_argument(&mut self, i: syn::GenericArgument) -> syn::GenericArgument {
+        i
+    }
+
+    fn fold_expr_repeat(&mut self, mut i: syn::ExprRepeat) -> syn::ExprRepeat {
+        *i.expr = self.fold_expr(*i.expr);
+        i
+    }
+
+    fn fold_expr_const(&mut self, mut i: syn::ExprConst) -> syn::ExprConst {
+        i.block = ConstClosures(self).fold_block(i.block);
+        i
+    }
+
+    fn fold_item_fn(&mut self, i: syn::ItemFn) -> syn::ItemFn {
+        if has_own_checked_attr(&i.attrs) {
+            return i;
+        }
+
+        let outer = self.enter_fn(&i.sig);
+        let i = syn::fold::fold_item_fn(self, i);
+        self.leave_fn(outer);
+        i
+    }
+
+    fn fold_impl_item_fn(&mut self, i: syn::ImplItemFn) -> syn::ImplItemFn {
+        if has_own_checked_attr(&i.attrs) {
+            return i;
+        }
+
+        let outer = self.enter_fn(&i.sig);
+        let i = syn::fold::fold_impl_item_fn(self, i);
+        self.leave_fn(outer);
+        i
+    }
+
+    fn fold_trait_item_fn(&mut self, i: syn::TraitItemFn) -> syn::TraitItemFn {
+        if has_own_checked_attr(&i.attrs) {
+            return i;
+        }
+
+        let outer = self.enter_fn(&i.sig);
+        let i = syn::fold::fold_trait_item_fn(self, i);
+        self.leave_fn(outer);
+        i
+    }
+
+    fn fold_item_const(&mut self, mut i: syn::ItemConst) -> syn::ItemConst {
+        *i.expr = ConstClosures(self).fold_expr(*i.expr);
+        i
+    }
+
+    fn fold_item_static(&mut self, mut i: syn::ItemStatic) -> syn::ItemStatic {
+        *i.expr = ConstClosures(self).fold_expr(*i.expr);
+        i
+    }
+
+    fn fold_impl_item_const(&mut self, mut i: syn::ImplItemConst) -> syn::ImplItemConst {
+        i.expr = ConstClosures(self).fold_expr(i.expr);
+        i
+    }
+
+    fn fold_trait_item_const(&mut self, mut i: syn::TraitItemConst) -> syn::TraitItemConst {
+        if let Some((eq, expr)) = i.default {
+            i.default = Some((eq, ConstClosures(self).fold_expr(expr)));
+        }
+
+        i
+    }
+
+    fn fold_expr_macro(&mut self, mut i: syn::ExprMacro) -> syn::ExprMacro {
+        i.mac = self.fold_macro_args(i.mac);
+        i
+    }
+
+    fn fold_stmt_macro(&mut self, mut i: syn::StmtMacro) -> syn::StmtMacro {
+        i.mac = self.fold_macro_args(i.mac);
+        i
+    }
+
     fn fold_expr(&mut self, e: syn::Expr) -> syn::Expr {
+        if let syn::Expr::Binary(_) | syn::Expr::Unary(_) = e {
+            match constant::fold(&e, located_span(e.span())) {
+                constant::Folded::Value(folded) => return folded,
+                constant::Folded::Error(error) => {
+                    self.errors.push(error);
+                    return e;
+                },
+                constant::Folded::NotConstant => {},
+            }
+        }
+
         match e {
             syn::Expr::Binary(mut binary) => {
                 *binary.left = self.fold_expr(*binary.left);
                 *binary.right = self.fold_expr(*binary.right);
 
                 if is_checked_binary_op(binary.op) {
-                    return checked_binary_expr(binary);
+                    return self.checked_binary_expr(binary);
                 }
 
                 if is_checked_binary_assign_op(binary.op) {
-                    return checked_binary_assign_expr(binary);
+                    return self.checked_binary_assign_expr(binary);
                 }
 
                 return syn::Expr::Binary(binary);
@@ -105,7 +720,7 @@ impl Fold for CheckedTransformer {
                 *unary.expr = self.fold_expr(*unary.expr);
 
                 if is_checked_unary_op(unary.op) {
-                    return checked_unary_expr(unary);
+                    return self.checked_unary_expr(unary);
                 }
 
                 return syn::Expr::Unary(unary);
@@ -113,13 +728,13 @@ impl Fold for CheckedTransformer {
 
             syn::Expr::Array(e) => syn::Expr::Array(self.fold_expr_array(e)),
             syn::Expr::Assign(e) => syn::Expr::Assign(self.fold_expr_assign(e)),
-            syn::Expr::Async(e) => syn::Expr::Async(self.fold_expr_async(e)),
+            e @ syn::Expr::Async(_) => self.fold_boundary_expr(e),
             syn::Expr::Await(e) => syn::Expr::Await(self.fold_expr_await(e)),
             syn::Expr::Block(e) => syn::Expr::Block(self.fold_expr_block(e)),
             syn::Expr::Break(e) => syn::Expr::Break(self.fold_expr_break(e)),
             syn::Expr::Call(e) => syn::Expr::Call(self.fold_expr_call(e)),
             syn::Expr::Cast(e) => syn::Expr::Cast(self.fold_expr_cast(e)),
-            syn::Expr::Closure(e) => syn::Expr::Closure(self.fold_expr_closure(e)),
+            e @ syn::Expr::Closure(_) => self.fold_boundary_expr(e),
             syn::Expr::Const(e) => syn::Expr::Const(self.fold_expr_const(e)),
             syn::Expr::Continue(e) => syn::Expr::Continue(self.fold_expr_continue(e)),
             syn::Expr::Field(e) => syn::Expr::Field(self.fold_expr_field(e)),
@@ -149,23 +764,267 @@ impl Fold for CheckedTransformer {
             syn::Expr::While(e) => syn::Expr::While(self.fold_expr_while(e)),
             syn::Expr::Yield(e) => syn::Expr::Yield(self.fold_expr_yield(e)),
             syn::Expr::Verbatim(e) => syn::Expr::Verbatim(e),
-            e => unimplemented!("Expression type not implemented: {e:?}"),
+            e => {
+                self.errors.push(syn::Error::new_spanned(&e, "unsupported expression in checked code"));
+                e
+            },
+        }
+    }
+}
+
+// Optional `crate = path;` prefix of the function-like macros.
+fn parse_namespace_override(input: syn::parse::ParseStream) -> syn::Result<Option<syn::Path>> {
+    if !(input.peek(syn::Token![crate]) && input.peek2(syn::Token![=])) {
+        return Ok(None);
+    }
+
+    input.parse::<syn::Token![crate]>()?;
+    input.parse::<syn::Token![=]>()?;
+    let namespace = input.call(syn::Path::parse_mod_style)?;
+    input.parse::<syn::Token![;]>()?;
+    Ok(Some(namespace))
+}
+
+// Folds the closures in a const-evaluated expression: they are only created at compile time, their
+// bodies run like any other code.
+struct ConstClosures<'a>(&'a mut CheckedTransformer);
+
+impl Fold for ConstClosures<'_> {
+    fn fold_expr(&mut self, i: syn::Expr) -> syn::Expr {
+        match i {
+            e @ syn::Expr::Closure(_) => {
+                let in_const = std::mem::replace(&mut self.0.in_const, false);
+                let e = self.0.fold_boundary_expr(e);
+                self.0.in_const = in_const;
+                e
+            },
+            i => syn::fold::fold_expr(self, i),
+        }
+    }
+
+    fn fold_item(&mut self, i: syn::Item) -> syn::Item {
+        self.0.fold_item(i)
+    }
+
+    fn fold_type(&mut self, i: syn::Type) -> syn::Type {
+        i
+    }
+}
+
+struct CheckedBody {
+    namespace: Option<syn::Path>,
+    stmts: Vec<syn::Stmt>,
+}
+
+impl syn::parse::Parse for CheckedBody {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let namespace = parse_namespace_override(input)?;
+        Ok(CheckedBody { namespace, stmts: syn::Block::parse_within(input)? })
+    }
+}
+
+impl CheckedBody {
+    fn transformer(&mut self, propagation: Propagation) -> CheckedTransformer {
+        CheckedTransformer::new(propagation).with_namespace(self.namespace.take())
+    }
+
+    fn fold(self, transformer: &mut CheckedTransformer) -> Self {
+        let stmts = self.stmts.into_iter().map(|stmt| transformer.fold_stmt(stmt)).collect();
+        CheckedBody { namespace: self.namespace, stmts }
+    }
+
+    fn split_tail(mut self) -> (Vec<syn::Stmt>, Option<syn::Expr>) {
+        match self.stmts.pop() {
+            Some(syn::Stmt::Expr(tail, None)) => (self.stmts, Some(tail)),
+            Some(stmt) => {
+                self.stmts.push(stmt);
+                (self.stmts, None)
+            },
+            None => (self.stmts, None),
+        }
+    }
+}
+
+impl ToTokens for CheckedBody {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self.stmts.as_slice() {
+            [syn::Stmt::Expr(expr, None)] => expr.to_tokens(tokens),
+            stmts => tokens.extend(quote::quote! { { #(#stmts)* } }),
+        }
+    }
+}
+
+struct CheckedClosure {
+    namespace: Option<syn::Path>,
+    closure: syn::ExprClosure,
+}
+
+impl syn::parse::Parse for CheckedClosure {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(CheckedClosure { namespace: parse_namespace_override(input)?, closure: input.parse()? })
+    }
+}
+
+#[derive(Default)]
+struct CheckedFnArgs {
+    panic: bool,
+    map_err: Option<syn::Expr>,
+    macros: Vec<syn::Ident>,
+    namespace: Option<syn::Path>,
+}
+
+impl CheckedFnArgs {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("panic") {
+            self.panic = true;
+            return Ok(());
+        }
+
+        if meta.path.is_ident("map_err") {
+            self.map_err = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
+
+        if meta.path.is_ident("macros") {
+            return meta.parse_nested_meta(|nested| {
+                let ident = nested.path.get_ident().ok_or_else(|| nested.error("expected a macro name"))?;
+                self.macros.push(ident.clone());
+                Ok(())
+            });
+        }
+
+        if meta.path.is_ident("crate") {
+            self.namespace = Some(meta.value()?.call(syn::Path::parse_mod_style)?);
+            return Ok(());
+        }
+
+        Err(meta.error("unsupported argument, expected `panic`, `map_err = ...`, `macros(...)` or `crate = ...`"))
+    }
+
+    fn transformer(self, propagation: Propagation) -> CheckedTransformer {
+        CheckedTransformer::new(propagation).with_namespace(self.namespace).with_macros(self.macros)
+    }
+
+    // Every fn then narrows this down with `Propagation::for_return_type`.
+    fn propagation(&self) -> Propagation {
+        match (self.panic, &self.map_err) {
+            (true, _) => Propagation::Panic,
+            (false, Some(map_err)) => Propagation::MapErr(map_err.clone()),
+            (false, None) => Propagation::Result,
         }
     }
 }
 
 #[proc_macro]
 pub fn checked(source: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let expr = syn::parse_macro_input!(source as syn::Expr);
-    let expr = CheckedTransformer.fold_expr(expr);
-    return expr.to_token_stream().into();
+    let mut body = syn::parse_macro_input!(source as CheckedBody);
+    let mut transformer = body.transformer(Propagation::Result);
+    let body = body.fold(&mut transformer);
+    return transformer.finish(body);
+}
+
+#[proc_macro]
+pub fn checked_or_panic(source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut body = syn::parse_macro_input!(source as CheckedBody);
+    let mut transformer = body.transformer(Propagation::Panic);
+    let body = body.fold(&mut transformer);
+    return transformer.finish(body);
+}
+
+#[proc_macro]
+pub fn const_checked(source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut body = syn::parse_macro_input!(source as CheckedBody);
+    let mut transformer = body.transformer(Propagation::Panic).with_const(true);
+    let body = body.fold(&mut transformer);
+    return transformer.finish(body);
+}
+
+#[proc_macro]
+pub fn try_checked(source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut body = syn::parse_macro_input!(source as CheckedBody);
+    let label = syn::Lifetime::new("'try_checked", proc_macro2::Span::mixed_site());
+    let mut transformer = body.transformer(Propagation::Break(label.clone()));
+    let ns = transformer.namespace.clone();
+    let (stmts, tail) = body.fold(&mut transformer).split_tail();
+    let tail = tail.unwrap_or_else(|| syn::parse_quote! { () });
+    return transformer.finish(quote::quote! {
+        #label: { #(#stmts)* ::core::result::Result::<_, #ns::CheckedMathError>::Ok(#tail) }
+    });
+}
+
+#[proc_macro]
+pub fn checked_closure(source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let CheckedClosure { namespace, mut closure } = syn::parse_macro_input!(source as CheckedClosure);
+    let mut transformer = CheckedTransformer::new(Propagation::Result).with_namespace(namespace);
+
+    if let syn::ReturnType::Default = closure.output {
+        let ns = transformer.namespace.clone();
+        let body = transformer.fold_expr(*closure.body);
+        let value = hygienic_ident("value");
+        closure.output = syn::parse_quote! { -> ::core::result::Result<_, #ns::CheckedMathError> };
+        *closure.body = syn::parse_quote! { { let #value = #body; ::core::result::Result::Ok(#value) } };
+    } else {
+        *closure.body = transformer.fold_expr(*closure.body);
+    }
+
+    return transformer.finish(closure);
 }
 
 #[proc_macro_attribute]
-pub fn checked_fn(_: proc_macro::TokenStream, source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+pub fn checked_fn(args: proc_macro::TokenStream, source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut fn_args = CheckedFnArgs::default();
+    let parser = syn::meta::parser(|meta| fn_args.parse(meta));
+    syn::parse_macro_input!(args with parser);
+
     let func = syn::parse_macro_input!(source as syn::ItemFn);
-    let func = CheckedTransformer.fold_item_fn(func);
-    return func.to_token_stream().into();
+    let propagation = fn_args.propagation().for_return_type(&func.sig.output);
+    let mut transformer = fn_args.transformer(propagation).with_const(func.sig.constness.is_some());
+    let func = syn::fold::fold_item_fn(&mut transformer, func);
+    return transformer.finish(func);
+}
+
+#[proc_macro_attribute]
+pub fn checked_impl(args: proc_macro::TokenStream, source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut fn_args = CheckedFnArgs::default();
+    let parser = syn::meta::parser(|meta| fn_args.parse(meta));
+    syn::parse_macro_input!(args with parser);
+
+    let propagation = fn_args.propagation();
+    let mut transformer = fn_args.transformer(propagation);
+
+    match syn::parse_macro_input!(source as syn::Item) {
+        syn::Item::Impl(item) => {
+            let item = syn::fold::fold_item_impl(&mut transformer, item);
+            return transformer.finish(item);
+        },
+
+        syn::Item::Trait(item) => {
+            let item = syn::fold::fold_item_trait(&mut transformer, item);
+            return transformer.finish(item);
+        },
+
+        item => {
+            return syn::Error::new_spanned(&item, "`checked_impl` expects an impl block or a trait").to_compile_error().into();
+        },
+    }
+}
+
+#[proc_macro_attribute]
+pub fn checked_mod(args: proc_macro::TokenStream, source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut fn_args = CheckedFnArgs::default();
+    let parser = syn::meta::parser(|meta| fn_args.parse(meta));
+    syn::parse_macro_input!(args with parser);
+
+    let item = syn::parse_macro_input!(source as syn::ItemMod);
+
+    if item.content.is_none() {
+        return syn::Error::new_spanned(&item, "`checked_mod` expects an inline module").to_compile_error().into();
+    }
+
+    let propagation = fn_args.propagation();
+    let mut transformer = fn_args.transformer(propagation);
+    let item = syn::fold::fold_item_mod(&mut transformer, item);
+    return transformer.finish(item);
 }
 
 #[proc_macro]
@@ -177,3 +1036,126 @@ pub fn unchecked(source: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn unchecked_fn(_: proc_macro::TokenStream, source: proc_macro::TokenStream) -> proc_macro::TokenStream {
     source
 }
+
+// Only read by the lint, which reports the reason next to the arithmetic it allows.
+#[proc_macro_attribute]
+pub fn allow_unchecked_math(args: proc_macro::TokenStream, source: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut reason = None;
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("reason") {
+            reason = Some(meta.value()?.parse::<syn::LitStr>()?);
+            return Ok(());
+        }
+
+        Err(meta.error("unsupported argument, expected `reason = \"...\"`"))
+    });
+
+    syn::parse_macro_input!(args with parser);
+
+    match reason {
+        Some(reason) if !reason.value().trim().is_empty() => source,
+        Some(reason) => syn::Error::new(reason.span(), "the reason for allowing unchecked math must not be empty").to_compile_error().into(),
+        None => syn::Error::new(proc_macro2::Span::call_site(), "expected `reason = \"...\"` explaining why unchecked math is allowed").to_compile_error().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn namespace_of(manifest: &str) -> Option<String> {
+        manifest_namespace(&manifest.parse().unwrap(), None)
+    }
+
+    fn namespace_in(manifest: &str, workspace: &str) -> Option<String> {
+        manifest_namespace(&manifest.parse().unwrap(), Some(&workspace.parse().unwrap()))
+    }
+
+    #[test]
+    fn test_manifest_namespace() {
+        assert_eq!(namespace_of(r#"
+            [package]
+            name = "pool"
+
+            [dependencies]
+            alloy-checked-math = { path = "../alloy-checked-math" }
+        "#), Some("::alloy_checked_math".to_string()));
+
+        assert_eq!(namespace_of(r#"
+            [package]
+            name = "pool"
+
+            [target.'cfg(unix)'.dev-dependencies]
+            checked-math = { package = "alloy-checked-math", version = "0.1" }
+        "#), Some("::checked_math".to_string()));
+
+        assert_eq!(namespace_of(r#"
+            [package]
+            name = "pool"
+
+            [package.metadata.alloy-checked-math]
+            crate = "crate::math"
+
+            [dependencies]
+            alloy-checked-math = "0.1"
+        "#), Some("crate::math".to_string()));
+
+        assert_eq!(namespace_of(r#"
+            [package]
+            name = "alloy-checked-math"
+        "#), None);
+
+        assert_eq!(namespace_of(r#"
+            [package]
+            name = "pool"
+
+            [dependencies]
+            prelude = { path = "../prelude" }
+        "#), None);
+    }
+
+    #[test]
+    fn test_workspace_namespace() {
+        let workspace = r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.dependencies]
+            math = { package = "alloy-checked-math", version = "0.1" }
+            prelude = { path = "crates/prelude" }
+        "#;
+
+        assert_eq!(namespace_in(r#"
+            [package]
+            name = "pool"
+
+            [dependencies]
+            math.workspace = true
+        "#, workspace), Some("::math".to_string()));
+
+        assert_eq!(namespace_in(r#"
+            [package]
+            name = "pool"
+
+            [dev-dependencies]
+            math = { workspace = true, features = ["std"] }
+        "#, workspace), Some("::math".to_string()));
+
+        assert_eq!(namespace_in(r#"
+            [package]
+            name = "pool"
+
+            [dependencies]
+            prelude.workspace = true
+        "#, workspace), None);
+
+        assert_eq!(namespace_of(r#"
+            [package]
+            name = "pool"
+
+            [dependencies]
+            math.workspace = true
+        "#), None);
+    }
+}
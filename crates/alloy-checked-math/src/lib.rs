@@ -1,8 +1,12 @@
-pub use alloy_checked_math_macro::{checked, unchecked, checked_fn, unchecked_fn};
+pub use alloy_checked_math_macro::{checked, checked_or_panic, const_checked, checked_closure, try_checked, unchecked, checked_fn, checked_impl, checked_mod, unchecked_fn, allow_unchecked_math};
 pub use alloy_checked_math_core::{CheckedMathError, Checked, CheckedPack, CheckedUnpack};
+pub use alloy_checked_math_core::{Quantity, Unit, Dimensionless, UnitMul, UnitDiv, Reduce};
+pub use alloy_checked_math_core::{TokenAmount, Rounding};
+pub use alloy_checked_math_core::Bounded;
+pub use alloy_checked_math_core::panicking;
 
 #[cfg(feature = "lint")]
-pub use alloy_checked_math_lint::{assert_checked, assert_checked_subtree, assert_checked_mod};
+pub use alloy_checked_math_lint::{assert_checked, assert_checked_with_config, assert_checked_subtree, assert_checked_mod, audit_path, check_path, fix_file, fix_path, Audit, AuditDiff, AuditFormat, Baseline, BaselineEntry, Config, EscapeHatch, EscapeHatchKind, Fix, FixOptions, FixPolicy, FloatArithmetic, Format, Location, Operator, Report, Severity, SourceSpan, Suppression, SuppressionKind, TestCode, Violation, ViolationKind};
 
 #[cfg(feature = "overridden_math")]
 pub use alloy_checked_math_core::define_checked;
@@ -0,0 +1,21 @@
+use alloy_checked_math::{checked, checked_fn, try_checked, CheckedMathError};
+
+// Targets of the facade share its manifest, and the expansions have to reach it by its name.
+fn fee(amount: u64, bps: u64) -> Result<u64, CheckedMathError> {
+    let fee = checked! { amount * bps / 10_000 };
+    return Ok(fee);
+}
+
+#[checked_fn]
+fn rebate(amount: u64, share: u64) -> Result<u64, CheckedMathError> {
+    Ok(amount * share / 100)
+}
+
+#[test]
+fn test_facade_targets() {
+    assert_eq!(fee(20_000, 30), Ok(60));
+    assert_eq!(rebate(60, 50), Ok(30));
+    assert!(fee(u64::MAX, 2).is_err());
+    let balance = 0u8;
+    assert!(try_checked!(balance - 1).is_err());
+}
@@ -0,0 +1,9 @@
+use alloy_checked_math::{checked_fn, CheckedMathError};
+
+#[checked_fn]
+fn accrue(balance: u64, interest: u64) -> Result<(), CheckedMathError> {
+    let _future = async move { balance + interest };
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use alloy_checked_math::checked_fn;
+
+#[checked_fn(saturating)]
+fn accrue(balance: u64, interest: u64) -> Result<u64, alloy_checked_math::CheckedMathError> {
+    Ok(balance + interest)
+}
+
+#[checked_fn(macros(std::vec))]
+fn ladder(base: u64, step: u64) -> Result<Vec<u64>, alloy_checked_math::CheckedMathError> {
+    Ok(vec![base + step])
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use alloy_checked_math::{checked_fn, CheckedMathError};
+
+#[checked_fn]
+fn scale_all(values: &[u64], rate: u64) -> Result<Vec<u64>, CheckedMathError> {
+    Ok(values.iter().map(|&value| value * rate).collect())
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use alloy_checked_math::const_checked;
+
+const LIMIT: u8 = 100;
+const TOO_BIG: u8 = const_checked!(LIMIT * 3);
+
+fn main() {
+    let _ = TOO_BIG;
+}
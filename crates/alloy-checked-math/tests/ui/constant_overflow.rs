@@ -0,0 +1,19 @@
+use alloy_checked_math::{checked_fn, CheckedMathError};
+use alloy_primitives::U256;
+
+#[checked_fn]
+fn fee(amount: u8) -> Result<u8, CheckedMathError> {
+    Ok(amount + 10u8 * 30)
+}
+
+#[checked_fn]
+fn ceiling() -> Result<U256, CheckedMathError> {
+    Ok(U256::MAX + U256::ONE)
+}
+
+#[checked_fn]
+fn share(amount: u32) -> Result<u32, CheckedMathError> {
+    Ok(amount * (100u32 / (5 - 5)))
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use alloy_checked_math::allow_unchecked_math;
+
+#[allow_unchecked_math(reason = " ")]
+fn next_epoch(epoch: u32) -> u32 {
+    epoch + 1
+}
+
+#[allow_unchecked_math]
+fn previous_epoch(epoch: u32) -> u32 {
+    epoch - 1
+}
+
+#[allow_unchecked_math(because = "epochs start at 1")]
+fn first_epoch(epoch: u32) -> u32 {
+    epoch - 1
+}
+
+fn main() {}
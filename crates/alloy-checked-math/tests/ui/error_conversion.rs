@@ -0,0 +1,17 @@
+use alloy_checked_math::checked_fn;
+
+#[derive(Debug)]
+enum PoolError {
+    Empty,
+}
+
+#[checked_fn]
+fn withdraw(reserve: u64, amount: u64) -> Result<u64, PoolError> {
+    if reserve == 0 {
+        return Err(PoolError::Empty);
+    }
+
+    Ok(reserve - amount)
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use alloy_checked_math::{checked_fn, CheckedMathError};
+
+struct Meters(u64);
+
+#[checked_fn]
+fn total(a: Meters, b: Meters) -> Result<Meters, CheckedMathError> {
+    Ok(a + b)
+}
+
+fn main() {}
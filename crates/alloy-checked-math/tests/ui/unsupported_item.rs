@@ -0,0 +1,8 @@
+use alloy_checked_math::checked_impl;
+
+#[checked_impl]
+struct Pool {
+    reserve: u64,
+}
+
+fn main() {}
@@ -0,0 +1,396 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use alloy_checked_math_lint::{Audit, AuditFormat, Baseline, Config, FixOptions, FixPolicy, Format, Report};
+
+const USAGE: &str = "\
+Usage: cargo checked-math check [PATHS]... [--package <NAME>]... [--workspace] [--config <FILE>] [--format <FORMAT>] [--fix]
+       cargo checked-math audit [PATHS]... [--package <NAME>]... [--workspace] [--config <FILE>] [--format <FORMAT>] [--diff <REV>]
+
+`check` checks that all arithmetic in the given files and directories goes through checked math.
+Without paths, checks the `src` directory of the selected packages, or of the current package. Each
+path is checked with the nearest `alloy-checked-math.toml` up to the workspace root, if there is one.
+
+`audit` lists every escape hatch from checked math in the same files: `unchecked!`, `#[unchecked_fn]`,
+suppressions and wrapping or saturating methods, with the arithmetic they let through and the
+justification attached to them.
+
+Options:
+  -p, --package <NAME>  Check the package with the given name, may be repeated
+      --workspace       Check every package of the workspace
+      --config <FILE>   Use this configuration file instead of looking for one
+      --format <FORMAT> Output format of `check`: text, json, sarif, junit or github [default: text],
+                        of `audit`: markdown or json [default: markdown]
+      --fix             Rewrite unchecked arithmetic into checked code before checking
+      --fix-policy <POLICY>
+                        How to rewrite it: checked-fn, checked-macro or unchecked-macro [default: checked-fn]
+      --dry-run         Print the fixes as a diff instead of writing them
+      --baseline <FILE> Only fail on violations that are not recorded in the baseline
+      --write-baseline <FILE>
+                        Record the current violations as the baseline and exit
+      --diff <REV>[..<REV>]
+                        Only audit the escape hatches added or removed since a git revision, up to the
+                        working tree or another revision, with paths relative to the repository root
+  -h, --help            Print this help
+
+`check` exits with 0 if no unchecked arithmetic was found, 1 if some was reported as an error and 2
+on any other error. With `--dry-run`, it exits with 1 if any file would be changed. `audit` exits with
+0 unless a file or revision could not be read.";
+
+#[derive(Default, PartialEq)]
+enum Command {
+    #[default]
+    Check,
+    Audit,
+}
+
+#[derive(Default)]
+struct Args {
+    command: Command,
+    paths: Vec<PathBuf>,
+    packages: Vec<String>,
+    workspace: bool,
+    config: Option<PathBuf>,
+    format: Format,
+    audit_format: AuditFormat,
+    diff: Option<String>,
+    fix: bool,
+    fix_options: FixOptions,
+    baseline: Option<PathBuf>,
+    write_baseline: Option<PathBuf>,
+}
+
+impl Args {
+    fn parse(command: Command, mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args { command, ..Args::default() };
+        let mut format = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-p" | "--package" => parsed.packages.push(args.next().ok_or(format!("`{arg}` expects a package name"))?),
+                "--workspace" => parsed.workspace = true,
+                "--config" => parsed.config = Some(args.next().ok_or("`--config` expects a file")?.into()),
+                "--baseline" => parsed.baseline = Some(args.next().ok_or("`--baseline` expects a file")?.into()),
+                "--write-baseline" => parsed.write_baseline = Some(args.next().ok_or("`--write-baseline` expects a file")?.into()),
+                "--fix" => parsed.fix = true,
+                "--dry-run" => parsed.fix_options.dry_run = true,
+                "--fix-policy" => parsed.fix_options.policy = args.next().ok_or("`--fix-policy` expects a policy")?.parse::<FixPolicy>()?,
+                "--diff" => parsed.diff = Some(args.next().ok_or("`--diff` expects a git revision")?),
+                "--format" => format = Some(args.next().ok_or("`--format` expects a format name")?),
+                arg if arg.starts_with("--format=") => format = Some(arg["--format=".len()..].to_string()),
+                arg if arg.starts_with("--package=") => parsed.packages.push(arg["--package=".len()..].to_string()),
+                arg if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+                path => parsed.paths.push(path.into()),
+            }
+        }
+
+        if parsed.fix_options.dry_run && !parsed.fix {
+            return Err("`--dry-run` requires `--fix`".to_string());
+        }
+
+        match parsed.command {
+            Command::Check if parsed.diff.is_some() => return Err("`--diff` is only supported by `audit`".to_string()),
+            Command::Check => parsed.format = format.as_deref().unwrap_or("text").parse()?,
+            Command::Audit if parsed.fix || parsed.baseline.is_some() || parsed.write_baseline.is_some() => {
+                return Err("`--fix`, `--baseline` and `--write-baseline` are only supported by `check`".to_string());
+            },
+            Command::Audit => parsed.audit_format = format.as_deref().unwrap_or("markdown").parse()?,
+        }
+
+        return Ok(parsed);
+    }
+
+    fn roots(&self) -> Result<Vec<PathBuf>, String> {
+        if !self.paths.is_empty() && self.packages.is_empty() && !self.workspace {
+            return Ok(self.paths.clone());
+        }
+
+        let packages = workspace_packages()?;
+
+        let selected: Vec<&Package> = if self.workspace {
+            packages.iter().collect()
+        } else if !self.packages.is_empty() {
+            let find = |name: &String| packages.iter().find(|package| &package.name == name).ok_or(format!("package `{name}` not found in the workspace"));
+            self.packages.iter().map(find).collect::<Result<_, _>>()?
+        } else {
+            let manifest = PathBuf::from(cargo(&["locate-project", "--message-format", "plain"])?.trim_end());
+
+            match packages.iter().find(|package| package.manifest == manifest) {
+                Some(package) => vec![package],
+                None => packages.iter().collect(),
+            }
+        };
+
+        // Relative paths keep the files changed by `--fix` short.
+        let current_dir = std::env::current_dir().map_err(|err| err.to_string())?;
+        let sources = selected.into_iter().map(|package| {
+            let source = package.manifest.parent().unwrap().join("src");
+            source.strip_prefix(&current_dir).map(PathBuf::from).unwrap_or(source)
+        });
+        return Ok(self.paths.iter().cloned().chain(sources).collect());
+    }
+}
+
+struct Package {
+    name: String,
+    manifest: PathBuf,
+}
+
+fn cargo(args: &[&str]) -> Result<String, String> {
+    let cargo = std::env::var_os("CARGO").unwrap_or("cargo".into());
+    let output = std::process::Command::new(cargo).args(args).output().map_err(|err| format!("failed to run cargo: {err}"))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim_end().to_string());
+    }
+
+    return String::from_utf8(output.stdout).map_err(|err| err.to_string());
+}
+
+fn workspace_packages() -> Result<Vec<Package>, String> {
+    let metadata = cargo(&["metadata", "--format-version", "1", "--no-deps"])?;
+    let metadata: serde_json::Value = serde_json::from_str(&metadata).map_err(|err| format!("invalid cargo metadata: {err}"))?;
+
+    let packages = metadata["packages"].as_array().ok_or("invalid cargo metadata: missing packages")?;
+
+    return Ok(packages.iter().filter_map(|package| Some(Package {
+        name: package["name"].as_str()?.to_string(),
+        manifest: package["manifest_path"].as_str()?.into(),
+    })).collect());
+}
+
+// Prints a diff or a summary of every changed file and returns whether there were any.
+fn fix(roots: &[(PathBuf, Config)], options: &FixOptions) -> std::io::Result<bool> {
+    let mut changed = false;
+
+    for (root, config) in roots {
+        for fix in alloy_checked_math_lint::fix_path(root, config, options)?.into_iter().filter(|fix| fix.is_changed()) {
+            changed = true;
+
+            match options.dry_run {
+                true => print!("{}", fix.diff()),
+                false => eprintln!("Fixed {}: {} functions made checked, {} expressions wrapped", fix.file.display(), fix.checked_fns, fix.wrapped),
+            }
+        }
+    }
+
+    return Ok(changed);
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new("git").args(args).output().map_err(|err| format!("failed to run git: {err}"))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim_end().to_string());
+    }
+
+    return String::from_utf8(output.stdout).map_err(|err| err.to_string());
+}
+
+// The `.rs` files under a path of the repository in a git revision, at their path in the working tree.
+fn git_sources(repository: &Path, revision: &str, path: &Path) -> Result<Vec<(PathBuf, String)>, String> {
+    let (repository_arg, path_arg) = (repository.to_string_lossy(), path.to_string_lossy());
+    let pathspec = if path_arg.is_empty() { "." } else { &path_arg };
+    let files = git(&["-C", &repository_arg, "ls-tree", "-r", "--name-only", "--full-name", revision, "--", pathspec])?;
+
+    return files.lines().filter(|file| file.ends_with(".rs")).map(|file| {
+        Ok((repository.join(file), git(&["-C", &repository_arg, "show", &format!("{revision}:{file}")])?))
+    }).collect();
+}
+
+// Reports are rooted at the workspace root wherever the command runs from, which keeps baselines
+// portable and machine readable reports usable as annotations in the repository. Outside of a
+// workspace, they are rooted at the current directory.
+fn workspace_root() -> PathBuf {
+    let root = match cargo(&["locate-project", "--workspace", "--message-format", "plain"]) {
+        Ok(manifest) => PathBuf::from(manifest.trim_end()).parent().unwrap().to_path_buf(),
+        Err(_) => std::env::current_dir().unwrap_or_default(),
+    };
+
+    return absolute(&root);
+}
+
+// Roots are checked at their absolute path so their files can be reported relative to the workspace
+// root.
+fn absolute(root: &Path) -> PathBuf {
+    root.canonicalize().or_else(|_| std::path::absolute(root)).unwrap_or_else(|_| root.to_path_buf())
+}
+
+// Audits the roots in the working tree, with paths relative to the workspace root like those of
+// `check`.
+fn audit(roots: &[(PathBuf, Config)]) -> Audit {
+    let mut merged = Audit { root: workspace_root(), ..Audit::default() };
+
+    for (root, config) in roots {
+        merged.merge(alloy_checked_math_lint::audit_path(absolute(root), config));
+    }
+
+    return merged;
+}
+
+// Audits the roots in the working tree, or in a git revision, with paths relative to the root of
+// the repository so both sides of a diff match wherever it runs from and however the roots are given.
+fn audit_revision(repository: &Path, roots: &[(PathBuf, Config)], revision: Option<&str>) -> Result<Audit, String> {
+    let mut merged = Audit { root: repository.to_path_buf(), ..Audit::default() };
+
+    for (root, config) in roots {
+        let absolute = root.canonicalize().or_else(|_| std::path::absolute(root)).map_err(|err| format!("{}: {err}", root.display()))?;
+        let path = absolute.strip_prefix(repository).map_err(|_| format!("{} is outside of the git repository {}", root.display(), repository.display()))?;
+        let root = repository.join(path);
+
+        merged.merge(match revision {
+            Some(revision) => Audit::from_sources(if root.is_file() { root.parent().unwrap() } else { &root }, git_sources(repository, revision, path)?, config),
+            None => alloy_checked_math_lint::audit_path(&root, config),
+        });
+    }
+
+    return Ok(merged);
+}
+
+// The audit, or its changes for `--diff`, and whether every file could be read.
+fn audit_output(roots: &[(PathBuf, Config)], diff: Option<&str>, format: AuditFormat) -> Result<(String, bool), String> {
+    let Some(diff) = diff else {
+        let audit = audit(roots);
+        return Ok((audit.format(format), audit.parse_errors.is_empty()));
+    };
+
+    let (base, head) = match diff.split_once("..") {
+        Some((base, head)) => (base, Some(head)),
+        None => (diff, None),
+    };
+
+    let repository = PathBuf::from(git(&["rev-parse", "--show-toplevel"])?.trim_end());
+    let repository = repository.canonicalize().map_err(|err| format!("{}: {err}", repository.display()))?;
+    let (base, head) = (audit_revision(&repository, roots, Some(base))?, audit_revision(&repository, roots, head)?);
+
+    for error in base.parse_errors.iter().chain(&head.parse_errors) {
+        eprintln!("error: failed to audit {}: {}", error.file.display(), error.message);
+    }
+
+    let readable = base.parse_errors.is_empty() && head.parse_errors.is_empty();
+    return Ok((head.diff(&base).format(format), readable));
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+
+    // `cargo checked-math` runs this binary as `cargo-checked-math checked-math ...`.
+    if args.peek().map(String::as_str) == Some("checked-math") {
+        args.next();
+    }
+
+    let command = match args.next().as_deref() {
+        Some("check") => Command::Check,
+        Some("audit") => Command::Audit,
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        },
+    };
+
+    let (args, roots) = match Args::parse(command, args).and_then(|args| Ok((args.roots()?, args))) {
+        Ok((roots, args)) => (args, roots),
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::from(2);
+        },
+    };
+
+    let configs: std::io::Result<Vec<_>> = roots.into_iter().map(|root| {
+        let config = match &args.config {
+            Some(path) => Config::load(path)?,
+            None => Config::discover(&root)?,
+        };
+
+        Ok((root, config))
+    }).collect();
+
+    let roots = match configs {
+        Ok(roots) => roots,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::from(2);
+        },
+    };
+
+    if args.command == Command::Audit {
+        return match audit_output(&roots, args.diff.as_deref(), args.audit_format) {
+            Ok((output, readable)) => {
+                print!("{output}");
+                if readable { ExitCode::SUCCESS } else { ExitCode::from(2) }
+            },
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::from(2)
+            },
+        };
+    }
+
+    if args.fix {
+        let changed = match fix(&roots, &args.fix_options) {
+            Ok(changed) => changed,
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::from(2);
+            },
+        };
+
+        if args.fix_options.dry_run {
+            return if changed { ExitCode::FAILURE } else { ExitCode::SUCCESS };
+        }
+    }
+
+    let mut merged = Report { root: workspace_root(), ..Report::default() };
+
+    for (root, config) in &roots {
+        merged.merge(alloy_checked_math_lint::check_path(absolute(root), config));
+    }
+
+    if let Some(path) = &args.write_baseline {
+        let baseline = Baseline::from_report(&merged);
+
+        if let Err(err) = baseline.save(path) {
+            eprintln!("error: failed to write {}: {err}", path.display());
+            return ExitCode::from(2);
+        }
+
+        eprintln!("Recorded {} unchecked arithmetic expressions in {}", baseline.len(), path.display());
+        return if merged.parse_errors.is_empty() { ExitCode::SUCCESS } else { ExitCode::from(2) };
+    }
+
+    if let Some(path) = &args.baseline {
+        let baseline = match Baseline::load(path) {
+            Ok(baseline) => baseline,
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::from(2);
+            },
+        };
+
+        let status = baseline.filter(&mut merged);
+        eprintln!("Ignored {} unchecked arithmetic expressions recorded in {}", status.known, path.display());
+
+        if !status.fixed.is_empty() {
+            eprintln!("These baseline entries were fixed and can be pruned with `--write-baseline`:");
+
+            for entry in &status.fixed {
+                eprintln!("  - {} in `{}`: {} ({}x)", entry.file, entry.function.as_deref().unwrap_or("unknown"), entry.expression, entry.count);
+            }
+        }
+    }
+
+    print!("{}", merged.format(args.format));
+
+    if !merged.parse_errors.is_empty() {
+        return ExitCode::from(2);
+    }
+
+    match merged.is_clean() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
@@ -0,0 +1,161 @@
+use std::process::Command;
+
+fn run(args: &[&str]) -> (Option<i32>, String) {
+    run_in(env!("CARGO_MANIFEST_DIR"), args)
+}
+
+fn run_in(current_dir: &str, args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-checked-math"))
+        .current_dir(current_dir)
+        .args(args)
+        .output()
+        .unwrap();
+
+    (output.status.code(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn test_check_paths() {
+    assert_eq!(run(&["check", "tests/fixtures/checked.rs"]).0, Some(0));
+
+    let (code, stdout) = run(&["checked-math", "check", "tests/fixtures"]);
+    assert_eq!(code, Some(1));
+    assert!(stdout.contains("error: unchecked arithmetic `amount * bps / 10_000` in `unchecked::fee`"));
+    assert!(stdout.contains(" --> crates/cargo-checked-math/tests/fixtures/unchecked.rs:2:18"));
+}
+
+#[test]
+fn test_formats() {
+    let (code, stdout) = run(&["check", "tests/fixtures", "--format", "github"]);
+    assert_eq!(code, Some(1));
+    assert_eq!(stdout, "::error file=crates/cargo-checked-math/tests/fixtures/unchecked.rs,line=2,col=18,endLine=2,endColumn=19,title=Unchecked arithmetic::unchecked arithmetic `amount * bps / 10_000` in `unchecked::fee`\n");
+    assert_eq!(run_in(concat!(env!("CARGO_MANIFEST_DIR"), "/tests"), &["check", "fixtures", "--format", "github"]), (code, stdout));
+
+    let (code, stdout) = run(&["check", "tests/fixtures/checked.rs", "tests/fixtures/unchecked.rs", "--format=json"]);
+    assert_eq!(code, Some(1));
+    assert_eq!(stdout.lines().count(), 1);
+
+    assert_eq!(run(&["check", "--format", "yaml"]).0, Some(2));
+}
+
+#[test]
+fn test_config() {
+    let (code, stdout) = run(&["check", "tests/fixtures/unchecked.rs", "--config", "tests/fixtures/warnings.toml", "--format", "github"]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.starts_with("::warning file=crates/cargo-checked-math/tests/fixtures/unchecked.rs,line=2,col=18,"));
+
+    assert_eq!(run(&["check", "tests/fixtures", "--config", "tests/fixtures/missing.toml"]).0, Some(2));
+}
+
+#[test]
+fn test_fix() {
+    let (code, stdout) = run(&["check", "tests/fixtures", "--fix", "--dry-run", "--fix-policy", "unchecked-macro"]);
+    assert_eq!(code, Some(1));
+    assert!(stdout.contains("+    alloy_checked_math::unchecked! { amount * bps / 10_000 }"));
+    assert_eq!(run(&["check", "tests/fixtures/checked.rs", "--fix", "--dry-run"]), (Some(0), String::new()));
+
+    let dir = std::env::temp_dir().join(format!("cargo-checked-math-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy("tests/fixtures/unchecked.rs", dir.join("unchecked.rs")).unwrap();
+
+    let (code, stdout) = run(&["check", dir.to_str().unwrap(), "--fix"]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("No unchecked arithmetic expressions found in the codebase."));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(run(&["check", "--dry-run"]).0, Some(2));
+    assert_eq!(run(&["check", "--fix", "--fix-policy", "magic"]).0, Some(2));
+}
+
+#[test]
+fn test_baseline() {
+    let dir = std::env::temp_dir().join(format!("cargo-checked-math-baseline-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let baseline = dir.join("baseline.json");
+    let baseline = baseline.to_str().unwrap();
+
+    assert_eq!(run(&["check", "tests/fixtures", "--write-baseline", baseline]).0, Some(0));
+    assert!(std::fs::read_to_string(baseline).unwrap().contains(r#""file": "crates/cargo-checked-math/tests/fixtures/unchecked.rs""#));
+    let (code, stdout) = run(&["check", "tests/fixtures", "--baseline", baseline]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.ends_with("No unchecked arithmetic expressions found in the codebase.\n"));
+    assert_eq!(run(&["check", "tests/fixtures", "--baseline", "missing.json"]).0, Some(2));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_check_packages() {
+    assert_eq!(run(&["check", "../alloy-checked-math-example/src/models"]).0, Some(0));
+
+    let (code, stdout) = run(&["check", "--package", "alloy-checked-math-example"]);
+    assert_eq!(code, Some(1));
+    assert!(stdout.contains("/src/non_models/example.rs ... found 2 unchecked arithmetic expressions"));
+    assert!(!stdout.contains("alloy-checked-math-core"));
+
+    assert_eq!(run(&["check", "--workspace"]).0, Some(1));
+}
+
+#[test]
+fn test_usage_errors() {
+    assert_eq!(run(&[]).0, Some(2));
+    assert_eq!(run(&["--help"]).0, Some(0));
+    assert_eq!(run(&["check", "--frobnicate"]).0, Some(2));
+    assert_eq!(run(&["check", "tests/fixtures/missing.rs"]).0, Some(2));
+    assert_eq!(run(&["check", "--package", "missing"]).0, Some(2));
+}
+
+#[test]
+fn test_audit() {
+    let (code, stdout) = run(&["audit", "tests/fixtures"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "# Escape hatches\n\nNo escape hatches found.\n");
+    assert_eq!(run(&["audit", "tests/fixtures", "--fix"]).0, Some(2));
+    assert_eq!(run(&["check", "tests/fixtures", "--diff", "HEAD"]).0, Some(2));
+
+    let dir = std::env::temp_dir().join(format!("cargo-checked-math-audit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+
+    let git = |args: &[&str]| assert!(Command::new("git").current_dir(&dir).args(args).output().unwrap().status.success());
+    let audit_in = |current_dir: &std::path::Path, args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_cargo-checked-math")).current_dir(current_dir).arg("audit").args(args).output().unwrap();
+        (output.status.code(), String::from_utf8(output.stdout).unwrap())
+    };
+    let audit = |args: &[&str]| audit_in(&dir, args);
+
+    std::fs::write(dir.join("src/lib.rs"), "fn next(epoch: u32) -> u32 {\n    epoch.wrapping_add(1)\n}\n").unwrap();
+    git(&["init", "--quiet"]);
+    git(&["add", "."]);
+    git(&["-c", "user.name=audit", "-c", "user.email=audit@example.com", "commit", "--quiet", "-m", "base"]);
+
+    std::fs::write(dir.join("src/lib.rs"), "fn next(epoch: u32) -> u32 {\n    // Epochs fit in a u32 for 80 years.\n    unchecked! { epoch + 1 }\n}\n").unwrap();
+
+    let (code, stdout) = audit(&["src", "--diff", "HEAD", "--format", "json"]);
+    assert_eq!(code, Some(0));
+
+    let changes: Vec<serde_json::Value> = stdout.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(changes.len(), 2);
+    assert_eq!((changes[0]["change"].as_str(), changes[0]["kind"].as_str()), (Some("added"), Some("unchecked_macro")));
+    assert_eq!(changes[0]["justification"].as_str(), Some("Epochs fit in a u32 for 80 years."));
+    assert_eq!((changes[1]["change"].as_str(), changes[1]["kind"].as_str()), (Some("removed"), Some("wrapping_method")));
+
+    // The same changes from a subdirectory, or with an absolute path, reported from the repository root.
+    std::fs::create_dir_all(dir.join("docs")).unwrap();
+    let absolute = dir.join("src");
+
+    for (current_dir, root) in [(dir.join("src"), "."), (dir.join("docs"), "../src"), (dir.join("docs"), absolute.to_str().unwrap())] {
+        let (code, subdir_stdout) = audit_in(&current_dir, &[root, "--diff", "HEAD", "--format", "json"]);
+        assert_eq!(code, Some(0));
+        assert_eq!(subdir_stdout, stdout);
+    }
+
+    assert!(changes.iter().all(|change| change["file"].as_str() == Some("src/lib.rs")));
+
+    let (code, stdout) = audit(&["src", "--diff", "HEAD..HEAD"]);
+    assert_eq!(code, Some(0));
+    assert!(stdout.ends_with("No escape hatches added or removed.\n"));
+    assert_eq!(audit(&["src", "--diff", "missing-revision"]).0, Some(2));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
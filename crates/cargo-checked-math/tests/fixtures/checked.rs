@@ -0,0 +1,4 @@
+#[checked_fn]
+fn fee(amount: u64, bps: u64) -> Result<u64, CheckedMathError> {
+    Ok(amount * bps / 10_000)
+}
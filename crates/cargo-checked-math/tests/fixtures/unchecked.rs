@@ -0,0 +1,3 @@
+fn fee(amount: u64, bps: u64) -> u64 {
+    amount * bps / 10_000
+}